@@ -0,0 +1,230 @@
+//! # Agent Registration Module
+//!
+//! Bounds the cost of `register_agent` spam. A first-time registration must
+//! attach `AGENT_REGISTRATION_DEPOSIT`, which is held with the worker entry
+//! and refunded by `deregister_agent`. Re-registering an existing agent needs
+//! no further deposit, but may change its codehash at most
+//! `MAX_CODEHASH_CHANGES` times per `CODEHASH_CHANGE_WINDOW_NS`. Codehashes
+//! are capped at `MAX_CODEHASH_LEN` bytes so an entry cannot outgrow the
+//! storage its deposit covers.
+
+use crate::*;
+
+/// Refundable NEAR deposit required to register a new worker agent.
+///
+/// Covers the storage of one `Worker` entry (~200 bytes) with headroom.
+pub const AGENT_REGISTRATION_DEPOSIT: NearToken = NearToken::from_millinear(10);
+
+/// Maximum codehash length: a hex-encoded SHA-256 digest.
+pub const MAX_CODEHASH_LEN: usize = 64;
+
+/// Codehash changes an agent may make within one window.
+pub const MAX_CODEHASH_CHANGES: u32 = 3;
+
+/// Length of the codehash change window (24 hours).
+pub const CODEHASH_CHANGE_WINDOW_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+impl Contract {
+    /// Registers `account_id` with `codehash`, or updates its codehash.
+    ///
+    /// Any deposit beyond what a first registration holds is refunded.
+    ///
+    /// # Panics
+    ///
+    /// - If `codehash` is longer than `MAX_CODEHASH_LEN` bytes
+    /// - If a first registration attaches less than `AGENT_REGISTRATION_DEPOSIT`
+    /// - If the agent already changed its codehash `MAX_CODEHASH_CHANGES`
+    ///   times in the current window
+    pub(crate) fn internal_register_agent(&mut self, account_id: AccountId, codehash: String) {
+        require!(
+            codehash.len() <= MAX_CODEHASH_LEN,
+            VaultError::CodehashTooLong {
+                len: codehash.len(),
+                maximum: MAX_CODEHASH_LEN
+            }
+            .to_string()
+        );
+        let attached = env::attached_deposit();
+        let now = env::block_timestamp();
+
+        let (worker, refund) = match self.worker_by_account_id.get(&account_id) {
+            None => {
+                require!(
+                    attached >= AGENT_REGISTRATION_DEPOSIT,
+                    VaultError::AgentDepositRequired {
+                        required: AGENT_REGISTRATION_DEPOSIT.as_yoctonear()
+                    }
+                    .to_string()
+                );
+                let worker = Worker {
+                    codehash,
+                    deposit: AGENT_REGISTRATION_DEPOSIT,
+                    codehash_changes: 0,
+                    window_start: U64(now),
+                };
+                (worker, attached.saturating_sub(AGENT_REGISTRATION_DEPOSIT))
+            }
+            Some(existing) if existing.codehash == codehash => (existing.clone(), attached),
+            Some(existing) => {
+                let mut worker = existing.clone();
+                if now.saturating_sub(worker.window_start.0) >= CODEHASH_CHANGE_WINDOW_NS {
+                    worker.window_start = U64(now);
+                    worker.codehash_changes = 0;
+                }
+                require!(
+                    worker.codehash_changes < MAX_CODEHASH_CHANGES,
+                    VaultError::CodehashChangeLimit {
+                        maximum: MAX_CODEHASH_CHANGES
+                    }
+                    .to_string()
+                );
+                worker.codehash_changes += 1;
+                worker.codehash = codehash;
+                (worker, attached)
+            }
+        };
+
+        self.worker_by_account_id.insert(account_id.clone(), worker);
+        if !refund.is_zero() {
+            let _ = Promise::new(account_id).transfer(refund);
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Removes the caller's worker registration and refunds its deposit.
+    ///
+    /// # Returns
+    ///
+    /// The refunded deposit, in yoctoNEAR. Agents registered before the
+    /// deposit was introduced get nothing back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the caller is not a registered agent.
+    pub fn deregister_agent(&mut self) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let worker = self
+            .worker_by_account_id
+            .remove(&account_id)
+            .unwrap_or_else(|| VaultError::AgentNotRegistered.panic());
+        if !worker.deposit.is_zero() {
+            let _ = Promise::new(account_id).transfer(worker.deposit);
+        }
+        U128(worker.deposit.as_yoctonear())
+    }
+
+    /// Returns the NEAR deposit a first-time `register_agent` must attach.
+    pub fn get_registration_deposit(&self) -> U128 {
+        U128(AGENT_REGISTRATION_DEPOSIT.as_yoctonear())
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::init_contract;
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn agent_ctx(deposit: NearToken, timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id("agent.test".parse().unwrap())
+            .attached_deposit(deposit)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    fn register(contract: &mut Contract, codehash: &str, deposit: NearToken, timestamp: u64) {
+        agent_ctx(deposit, timestamp);
+        assert!(contract.register_agent(codehash.to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "E_AGENT_DEPOSIT")]
+    fn first_registration_requires_deposit() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        register(&mut contract, "abc", NearToken::from_yoctonear(0), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_CODEHASH_TOO_LONG")]
+    fn oversized_codehash_is_rejected() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        let codehash = "a".repeat(MAX_CODEHASH_LEN + 1);
+        register(&mut contract, &codehash, AGENT_REGISTRATION_DEPOSIT, 0);
+    }
+
+    #[test]
+    fn deregistration_refunds_deposit() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        register(&mut contract, "abc", AGENT_REGISTRATION_DEPOSIT, 0);
+        assert_eq!(
+            contract.get_agent("agent.test".parse().unwrap()).deposit,
+            AGENT_REGISTRATION_DEPOSIT
+        );
+
+        agent_ctx(NearToken::from_yoctonear(0), 0);
+        assert_eq!(
+            contract.deregister_agent(),
+            contract.get_registration_deposit()
+        );
+        assert!(contract.worker_by_account_id.is_empty());
+        assert_eq!(get_created_receipts().len(), 1);
+    }
+
+    #[test]
+    fn re_registration_needs_no_new_deposit() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        register(&mut contract, "abc", AGENT_REGISTRATION_DEPOSIT, 0);
+        register(&mut contract, "def", NearToken::from_yoctonear(0), 1);
+
+        let worker = contract.get_agent("agent.test".parse().unwrap());
+        assert_eq!(worker.codehash, "def");
+        assert_eq!(worker.deposit, AGENT_REGISTRATION_DEPOSIT);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_CODEHASH_CHANGE_LIMIT")]
+    fn codehash_thrash_within_window_is_rejected() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        register(&mut contract, "h-0", AGENT_REGISTRATION_DEPOSIT, 0);
+        for change in 1..=MAX_CODEHASH_CHANGES + 1 {
+            register(
+                &mut contract,
+                &format!("h-{}", change),
+                NearToken::from_yoctonear(0),
+                change as u64,
+            );
+        }
+    }
+
+    #[test]
+    fn codehash_changes_reset_after_window() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        register(&mut contract, "h-0", AGENT_REGISTRATION_DEPOSIT, 0);
+        for change in 1..=MAX_CODEHASH_CHANGES {
+            register(
+                &mut contract,
+                &format!("h-{}", change),
+                NearToken::from_yoctonear(0),
+                change as u64,
+            );
+        }
+        register(
+            &mut contract,
+            "h-next",
+            NearToken::from_yoctonear(0),
+            CODEHASH_CHANGE_WINDOW_NS,
+        );
+        assert_eq!(
+            contract.get_agent("agent.test".parse().unwrap()).codehash,
+            "h-next"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! # Asset Metadata Module
+//!
+//! Confirms `asset_decimals` against the asset token itself. `init` schedules
+//! an `ft_metadata` call on the asset, and `on_asset_metadata` adopts the
+//! reported decimals, which in turn set the minimum deposit (one whole token,
+//! `10^asset_decimals`) and rescale the minimum first deposit.
+//!
+//! The decimals passed to `init` stay in place when the call fails, when the
+//! metadata cannot be parsed, or once the vault holds assets or shares. In
+//! the last case `set_asset` is the way to change them, since it rescales
+//! every asset-denominated amount.
+
+use crate::*;
+use near_contract_standards::fungible_token::metadata::ext_ft_metadata;
+use near_sdk::{ext_contract, PromiseResult};
+
+/// Gas for the asset's `ft_metadata` view.
+const GAS_FOR_FT_METADATA: Gas = Gas::from_tgas(5);
+
+/// Gas for the `on_asset_metadata` callback.
+const GAS_FOR_ASSET_METADATA_CALLBACK: Gas = Gas::from_tgas(5);
+
+/// Callback interface for the asset metadata lookup.
+#[allow(dead_code)]
+#[ext_contract(ext_self)]
+trait ExtContract {
+    fn on_asset_metadata(&mut self) -> u8;
+}
+
+/// Fetches the metadata of `asset` and reports it to `on_asset_metadata`.
+pub(crate) fn fetch_asset_decimals(asset: AccountId) -> Promise {
+    ext_ft_metadata::ext(asset)
+        .with_static_gas(GAS_FOR_FT_METADATA)
+        .ft_metadata()
+        .then(
+            ext_self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_ASSET_METADATA_CALLBACK)
+                .on_asset_metadata(),
+        )
+}
+
+impl Contract {
+    /// Adopts `decimals` as the asset's decimals if the vault is still empty.
+    ///
+    /// Share decimals follow as `decimals + extra_decimals`, and the minimum
+    /// first deposit is rescaled so it keeps its value in whole tokens.
+    fn internal_apply_asset_decimals(&mut self, decimals: u8) {
+        if self.token.total_supply > 0 || self.total_assets > 0 {
+            self.log_debug(&format!(
+                "on_asset_metadata: vault in use, keeping asset_decimals={} (asset reports {})",
+                self.asset_decimals, decimals
+            ));
+            return;
+        }
+        let share_decimals = decimals
+            .checked_add(self.extra_decimals)
+            .filter(|share_decimals| 10u128.checked_pow(*share_decimals as u32).is_some());
+        let Some(share_decimals) = share_decimals else {
+            self.log_debug(&format!(
+                "on_asset_metadata: asset decimals {} unsupported, keeping {}",
+                decimals, self.asset_decimals
+            ));
+            return;
+        };
+
+        let old_decimals = self.asset_decimals;
+        self.min_first_deposit = if decimals >= old_decimals {
+            self.min_first_deposit
+                .saturating_mul(10u128.pow((decimals - old_decimals) as u32))
+        } else {
+            self.min_first_deposit / 10u128.pow((old_decimals - decimals) as u32)
+        };
+        self.asset_decimals = decimals;
+        self.metadata.decimals = share_decimals;
+    }
+}
+
+#[near]
+impl Contract {
+    /// Re-reads the asset's decimals from its `ft_metadata`.
+    ///
+    /// Only takes effect while the vault holds no assets or shares.
+    ///
+    /// # Returns
+    ///
+    /// A promise resolving to the asset decimals in effect afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn refresh_asset_decimals(&mut self) -> Promise {
+        self.require_owner();
+        fetch_asset_decimals(self.asset.clone())
+    }
+
+    /// Callback with the asset's `ft_metadata`.
+    ///
+    /// # Returns
+    ///
+    /// The asset decimals in effect after the callback.
+    #[private]
+    pub fn on_asset_metadata(&mut self) -> u8 {
+        let decimals = match env::promise_result(0) {
+            PromiseResult::Successful(bytes) => {
+                serde_json::from_slice::<FungibleTokenMetadata>(&bytes)
+                    .ok()
+                    .map(|metadata| metadata.decimals)
+            }
+            _ => None,
+        };
+        match decimals {
+            Some(decimals) if decimals != self.asset_decimals => {
+                self.internal_apply_asset_decimals(decimals)
+            }
+            Some(_) => {}
+            None => self.log_debug(&format!(
+                "on_asset_metadata: lookup failed, keeping asset_decimals={}",
+                self.asset_decimals
+            )),
+        }
+        self.asset_decimals
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::{init_callback_ctx, init_contract};
+
+    /// Delivers asset metadata reporting `decimals` to the callback.
+    fn metadata_result(decimals: u8) -> PromiseResult {
+        let metadata = FungibleTokenMetadata {
+            spec: "ft-1.0.0".to_string(),
+            name: "Asset".to_string(),
+            symbol: "AST".to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals,
+        };
+        PromiseResult::Successful(serde_json::to_vec(&metadata).unwrap())
+    }
+
+    #[test]
+    fn reported_decimals_set_the_minimum_deposit() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        init_callback_ctx(metadata_result(8));
+        assert_eq!(contract.on_asset_metadata(), 8);
+
+        let config = contract.get_vault_config();
+        assert_eq!(config.asset_decimals, 8);
+        assert_eq!(config.share_decimals, 11);
+        assert_eq!(config.min_deposit, U128(100_000_000));
+        assert_eq!(config.min_first_deposit, U128(100_000_000));
+    }
+
+    #[test]
+    fn reported_decimals_rescale_a_custom_first_deposit() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        contract.set_min_first_deposit(U128(5_000_000));
+        init_callback_ctx(metadata_result(8));
+        contract.on_asset_metadata();
+        assert_eq!(contract.min_first_deposit, 500_000_000);
+
+        init_callback_ctx(metadata_result(2));
+        contract.on_asset_metadata();
+        assert_eq!(contract.min_first_deposit, 500);
+    }
+
+    #[test]
+    fn failed_lookup_keeps_configured_decimals() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        init_callback_ctx(PromiseResult::Failed);
+        assert_eq!(contract.on_asset_metadata(), 6);
+        assert_eq!(contract.get_vault_config().min_deposit, U128(1_000_000));
+    }
+
+    #[test]
+    fn vault_in_use_keeps_configured_decimals() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        contract.total_assets = 5_000_000;
+        init_callback_ctx(metadata_result(8));
+        assert_eq!(contract.on_asset_metadata(), 6);
+        assert_eq!(contract.metadata.decimals, 9);
+    }
+}
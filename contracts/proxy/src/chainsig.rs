@@ -14,16 +14,67 @@
 //! The `internal_request_signature` function is called by the main contract
 //! to sign payloads using a derived key path. The MPC network returns the
 //! signature asynchronously via a cross-contract callback.
+//!
+//! ## Intent Auto-Advance
+//!
+//! A solver signing the destination-chain deposit for an intent can pass its
+//! `intent_index` to `request_signature`. Once the signature comes back, the
+//! `on_intent_signed` callback moves the intent from `StpLiquidityBorrowed`
+//! to `StpLiquidityDeposited`. A failed signature leaves the intent as is.
 
 use crate::*;
 
-use near_sdk::ext_contract;
+use crate::intents::State;
+use crate::vault_standards::events::IntentStateChanged;
+use near_sdk::{ext_contract, PromiseResult};
 use serde::Serialize;
+use std::str::FromStr;
 
 // ============================================================================
 // Data Structures
 // ============================================================================
 
+/// Signature scheme requested from the MPC network.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyType {
+    /// secp256k1 ECDSA (domain 0).
+    Ecdsa,
+    /// ed25519 EdDSA (domain 1).
+    Eddsa,
+}
+
+impl KeyType {
+    /// Returns the MPC domain identifier for this key type.
+    pub fn domain_id(&self) -> u64 {
+        match self {
+            KeyType::Ecdsa => 0,
+            KeyType::Eddsa => 1,
+        }
+    }
+
+    /// Returns the expected decoded payload length in bytes.
+    pub fn payload_len(&self) -> usize {
+        match self {
+            KeyType::Ecdsa | KeyType::Eddsa => 32,
+        }
+    }
+}
+
+impl FromStr for KeyType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Ecdsa" => Ok(KeyType::Ecdsa),
+            "Eddsa" => Ok(KeyType::Eddsa),
+            other => Err(format!(
+                "Unsupported key_type \"{}\", expected \"Ecdsa\" or \"Eddsa\"",
+                other
+            )),
+        }
+    }
+}
+
 /// Payload wrapper for the MPC sign request.
 ///
 /// The payload type determines which signing algorithm is used.
@@ -58,6 +109,17 @@ trait MPCContract {
     fn sign(&self, request: SignRequest);
 }
 
+/// Callback interface for intent-linked signature requests.
+#[allow(dead_code)]
+#[ext_contract(ext_self)]
+trait ExtContract {
+    fn on_intent_signed(
+        &mut self,
+        solver_id: AccountId,
+        intent_index: U128,
+    ) -> Option<near_sdk::serde_json::Value>;
+}
+
 // ============================================================================
 // Constants
 // ============================================================================
@@ -65,9 +127,48 @@ trait MPCContract {
 /// Gas allocation for MPC sign request.
 const GAS: Gas = Gas::from_tgas(10);
 
+/// Gas for the `on_intent_signed` callback.
+const GAS_FOR_INTENT_SIGNED: Gas = Gas::from_tgas(10);
+
 /// Deposit required for MPC sign request (1 yoctoNEAR).
 const ATTACHED_DEPOSIT: NearToken = NearToken::from_yoctonear(1);
 
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Validates the signature request arguments before any gas is spent on the
+/// MPC call.
+///
+/// # Returns
+///
+/// The parsed `KeyType`.
+///
+/// # Panics
+///
+/// - If `key_type` is not `"Ecdsa"` or `"Eddsa"`
+/// - If `path` is empty
+/// - If `payload` is not hex of the length expected for the key type
+pub fn validate_signature_request(path: &str, payload: &str, key_type: &str) -> KeyType {
+    let key_type = KeyType::from_str(key_type).unwrap_or_else(|err| env::panic_str(&err));
+
+    require!(!path.trim().is_empty(), "Derivation path must not be empty");
+
+    let bytes = hex::decode(payload)
+        .unwrap_or_else(|_| env::panic_str("Payload must be a hex-encoded hash"));
+    require!(
+        bytes.len() == key_type.payload_len(),
+        format!(
+            "Payload must be {} bytes for {:?}, got {}",
+            key_type.payload_len(),
+            key_type,
+            bytes.len()
+        )
+    );
+
+    key_type
+}
+
 // ============================================================================
 // Internal Functions
 // ============================================================================
@@ -87,15 +188,21 @@ const ATTACHED_DEPOSIT: NearToken = NearToken::from_yoctonear(1);
 ///
 /// A promise that resolves to the signature result.
 ///
+/// # Panics
+///
+/// Panics if the arguments fail `validate_signature_request`.
+///
 /// # MPC Contract Selection
 ///
 /// The function automatically selects the appropriate MPC contract:
 /// - Testnet: `v1.signer-prod.testnet`
 /// - Mainnet: `v1.signer`
 pub fn internal_request_signature(path: String, payload: String, key_type: String) -> Promise {
-    let (payload_v2, domain_id) = match key_type.as_str() {
-        "Eddsa" => (Payload::Eddsa(payload), 1),
-        _ => (Payload::Ecdsa(payload), 0),
+    let key_type = validate_signature_request(&path, &payload, &key_type);
+    let domain_id = key_type.domain_id();
+    let payload_v2 = match key_type {
+        KeyType::Eddsa => Payload::Eddsa(payload),
+        KeyType::Ecdsa => Payload::Ecdsa(payload),
     };
 
     let request = SignRequest {
@@ -123,3 +230,219 @@ pub fn internal_request_signature(path: String, payload: String, key_type: Strin
         .with_attached_deposit(ATTACHED_DEPOSIT)
         .sign(request)
 }
+
+// ============================================================================
+// Intent Auto-Advance
+// ============================================================================
+
+impl Contract {
+    /// Whether `intent_index` is one of `solver_id`'s open intents.
+    fn solver_owns_intent(&self, solver_id: &AccountId, intent_index: u128) -> bool {
+        self.solver_id_to_indices
+            .get(solver_id)
+            .is_some_and(|indices| indices.contains(&intent_index))
+    }
+
+    /// Requests a signature and, once it succeeds, advances `intent_index`
+    /// out of `StpLiquidityBorrowed`.
+    ///
+    /// # Panics
+    ///
+    /// - If the caller doesn't own the intent
+    /// - If the intent is not in `StpLiquidityBorrowed`
+    pub(crate) fn internal_request_intent_signature(
+        &mut self,
+        path: String,
+        payload: String,
+        key_type: String,
+        intent_index: u128,
+    ) -> Promise {
+        let solver_id = env::predecessor_account_id();
+        require!(
+            self.solver_owns_intent(&solver_id, intent_index),
+            VaultError::IntentNotOwned.to_string()
+        );
+        let intent = self
+            .index_to_intent
+            .get(&intent_index)
+            .unwrap_or_else(|| VaultError::IntentNotFound.panic());
+        require!(
+            intent.state == State::StpLiquidityBorrowed,
+            format!(
+                "Intent in state {:?} cannot advance on signature",
+                intent.state
+            )
+        );
+
+        internal_request_signature(path, payload, key_type).then(
+            ext_self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_INTENT_SIGNED)
+                .on_intent_signed(solver_id, U128(intent_index)),
+        )
+    }
+}
+
+#[near]
+impl Contract {
+    /// Callback after an intent-linked signature request.
+    ///
+    /// On success, moves the intent to `StpLiquidityDeposited` if its solver
+    /// still owns it and it is still `StpLiquidityBorrowed`; otherwise
+    /// nothing changes. Nothing is rolled back on failure.
+    ///
+    /// # Returns
+    ///
+    /// The MPC signature, or `None` if signing failed.
+    #[private]
+    pub fn on_intent_signed(
+        &mut self,
+        solver_id: AccountId,
+        intent_index: U128,
+    ) -> Option<near_sdk::serde_json::Value> {
+        let PromiseResult::Successful(bytes) = env::promise_result(0) else {
+            return None;
+        };
+
+        let owned = self.solver_owns_intent(&solver_id, intent_index.0);
+        match self.index_to_intent.get(&intent_index.0) {
+            Some(intent) if owned && intent.state == State::StpLiquidityBorrowed => {
+                let new_state = State::StpLiquidityDeposited;
+                IntentStateChanged {
+                    solver_id: &solver_id,
+                    intent_index,
+                    old_state: &intent.state,
+                    new_state: &new_state,
+                }
+                .emit(self.event_standard());
+                let mut intent = intent.clone();
+                intent.state = new_state;
+                self.index_to_intent.insert(intent_index.0, intent);
+            }
+            _ => self.log_debug(&format!(
+                "on_intent_signed: intent {} not advanced",
+                intent_index.0
+            )),
+        }
+
+        near_sdk::serde_json::from_slice(&bytes).ok()
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::builders::ContractBuilder;
+    use crate::test_utils::helpers::{init_callback_ctx, init_ctx};
+
+    const HASH: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+    #[test]
+    fn key_type_parses_supported_values() {
+        assert_eq!("Ecdsa".parse::<KeyType>(), Ok(KeyType::Ecdsa));
+        assert_eq!("Eddsa".parse::<KeyType>(), Ok(KeyType::Eddsa));
+        assert!("ecdsa".parse::<KeyType>().is_err());
+    }
+
+    #[test]
+    fn valid_request_passes_validation() {
+        init_ctx("alice.test", 0);
+        assert_eq!(
+            validate_signature_request("ethereum-1", HASH, "Ecdsa"),
+            KeyType::Ecdsa
+        );
+        assert_eq!(
+            validate_signature_request("solana-1", HASH, "Eddsa"),
+            KeyType::Eddsa
+        );
+        let _ = internal_request_signature(
+            "ethereum-1".to_string(),
+            HASH.to_string(),
+            "Ecdsa".to_string(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported key_type")]
+    fn unknown_key_type_is_rejected() {
+        init_ctx("alice.test", 0);
+        validate_signature_request("ethereum-1", HASH, "Secp256k1");
+    }
+
+    #[test]
+    #[should_panic(expected = "Derivation path must not be empty")]
+    fn empty_path_is_rejected() {
+        init_ctx("alice.test", 0);
+        validate_signature_request("  ", HASH, "Ecdsa");
+    }
+
+    #[test]
+    #[should_panic(expected = "Payload must be a hex-encoded hash")]
+    fn non_hex_payload_is_rejected() {
+        init_ctx("alice.test", 0);
+        validate_signature_request("ethereum-1", "not-hex", "Ecdsa");
+    }
+
+    #[test]
+    #[should_panic(expected = "Payload must be 32 bytes for Eddsa, got 2")]
+    fn short_payload_is_rejected() {
+        init_ctx("alice.test", 0);
+        validate_signature_request("solana-1", "abcd", "Eddsa");
+    }
+
+    /// A contract where `solver.test` holds intent 0 in `StpLiquidityBorrowed`.
+    fn contract_with_borrowed_intent() -> Contract {
+        ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .intent("solver.test", "h-0", 1_000_000)
+            .build()
+    }
+
+    fn state_of(contract: &Contract, index: u128) -> State {
+        contract.index_to_intent.get(&index).unwrap().state.clone()
+    }
+
+    #[test]
+    fn intent_advances_when_signature_succeeds() {
+        let mut contract = contract_with_borrowed_intent();
+        init_ctx("solver.test", 0);
+        let _ = contract.request_signature(
+            "ethereum-1".to_string(),
+            HASH.to_string(),
+            "Ecdsa".to_string(),
+            Some(U128(0)),
+        );
+
+        init_callback_ctx(PromiseResult::Successful(b"{\"big_r\":\"r\"}".to_vec()));
+        let signature = contract.on_intent_signed("solver.test".parse().unwrap(), U128(0));
+
+        assert_eq!(signature.unwrap()["big_r"], "r");
+        assert_eq!(state_of(&contract, 0), State::StpLiquidityDeposited);
+    }
+
+    #[test]
+    fn intent_is_unchanged_when_signature_fails() {
+        let mut contract = contract_with_borrowed_intent();
+        init_callback_ctx(PromiseResult::Failed);
+        let signature = contract.on_intent_signed("solver.test".parse().unwrap(), U128(0));
+
+        assert!(signature.is_none());
+        assert_eq!(state_of(&contract, 0), State::StpLiquidityBorrowed);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_INTENT_NOT_OWNED")]
+    fn intent_signature_requires_intent_owner() {
+        let mut contract = contract_with_borrowed_intent();
+        init_ctx("other.test", 0);
+        let _ = contract.request_signature(
+            "ethereum-1".to_string(),
+            HASH.to_string(),
+            "Ecdsa".to_string(),
+            Some(U128(0)),
+        );
+    }
+}
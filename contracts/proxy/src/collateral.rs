@@ -0,0 +1,177 @@
+//! # Solver Collateral Module
+//!
+//! Lets solvers post vault shares as collateral. Locked shares stay in the
+//! solver's balance (and keep earning yield) but cannot be redeemed or
+//! transferred until the owner releases them.
+//!
+//! ## Collateral Flow
+//!
+//! 1. Solver calls `ft_transfer_call` on the asset with a `deposit_collateral` message
+//! 2. Shares are minted through the normal deposit path
+//! 3. The minted shares are immediately locked for the solver
+//! 4. The owner calls `release_collateral` to unlock shares
+
+use crate::*;
+use near_contract_standards::fungible_token::core::FungibleTokenCore;
+use near_sdk::json_types::U128;
+
+impl Contract {
+    /// Returns the number of shares locked as collateral for an account.
+    pub(crate) fn internal_locked_shares(&self, account_id: &AccountId) -> u128 {
+        self.locked_collateral.get(account_id).copied().unwrap_or(0)
+    }
+
+    /// Returns the account's share balance that is not locked as collateral.
+    pub(crate) fn internal_unlocked_shares(&self, account_id: &AccountId) -> u128 {
+        self.token
+            .ft_balance_of(account_id.clone())
+            .0
+            .saturating_sub(self.internal_locked_shares(account_id))
+    }
+
+    /// Locks `shares` of the account's balance as collateral.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the account does not hold enough unlocked shares.
+    pub(crate) fn internal_lock_collateral(&mut self, account_id: &AccountId, shares: u128) {
+        require!(
+            self.internal_unlocked_shares(account_id) >= shares,
+            "Insufficient unlocked shares for collateral"
+        );
+        let locked = self
+            .internal_locked_shares(account_id)
+            .checked_add(shares)
+            .expect("locked collateral overflow");
+        self.locked_collateral.insert(account_id.clone(), locked);
+
+        env::log_str(&format!(
+            "collateral_locked account={} shares={} total_locked={}",
+            account_id, shares, locked
+        ));
+    }
+
+    /// Asserts that moving `amount` shares out of `account_id` leaves its
+    /// locked collateral covered.
+    pub(crate) fn assert_unlocked_shares(&self, account_id: &AccountId, amount: u128) {
+        require!(
+            self.internal_unlocked_shares(account_id) >= amount,
+            "Shares are locked as collateral"
+        );
+    }
+}
+
+#[near]
+impl Contract {
+    /// Releases shares previously locked as solver collateral.
+    ///
+    /// # Arguments
+    ///
+    /// * `solver_id` - The solver whose collateral is released
+    /// * `shares` - Number of locked shares to release
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `shares` exceeds the solver's locked collateral
+    pub fn release_collateral(&mut self, solver_id: AccountId, shares: U128) {
+        self.require_not_paused_for(PauseFlag::Borrows);
+        self.require_owner();
+
+        let locked = self.internal_locked_shares(&solver_id);
+        let remaining = locked
+            .checked_sub(shares.0)
+            .unwrap_or_else(|| env::panic_str("Release exceeds locked collateral"));
+
+        if remaining == 0 {
+            self.locked_collateral.remove(&solver_id);
+        } else {
+            self.locked_collateral.insert(solver_id.clone(), remaining);
+        }
+
+        env::log_str(&format!(
+            "collateral_released account={} shares={} total_locked={}",
+            solver_id, shares.0, remaining
+        ));
+    }
+
+    /// Returns the number of shares locked as collateral for an account.
+    pub fn get_locked_collateral(&self, account_id: AccountId) -> U128 {
+        U128(self.internal_locked_shares(&account_id))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::{init_contract, init_ctx};
+    use crate::vault_standards::VaultCore;
+    use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+
+    fn deposit_collateral(contract: &mut Contract, solver: &AccountId, amount: u128) {
+        init_ctx("usdc.test", 0);
+        let msg = serde_json::json!({ "deposit_collateral": {} }).to_string();
+        let _ = contract.ft_on_transfer(solver.clone(), U128(amount), msg);
+    }
+
+    #[test]
+    fn deposit_collateral_locks_minted_shares() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        let solver: AccountId = "solver.test".parse().unwrap();
+        contract.token.internal_register_account(&solver);
+
+        deposit_collateral(&mut contract, &solver, 5_000_000);
+
+        let balance = contract.ft_balance_of(solver.clone()).0;
+        assert_eq!(balance, 5_000_000_000);
+        assert_eq!(contract.get_locked_collateral(solver.clone()).0, balance);
+        assert_eq!(contract.max_redeem(solver).0, 0);
+        assert_eq!(contract.total_assets, 5_000_000);
+    }
+
+    #[test]
+    fn plain_deposit_stays_redeemable_next_to_collateral() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        let solver: AccountId = "solver.test".parse().unwrap();
+        contract.token.internal_register_account(&solver);
+
+        deposit_collateral(&mut contract, &solver, 5_000_000);
+        let msg = serde_json::json!({ "deposit": {} }).to_string();
+        let _ = contract.ft_on_transfer(solver.clone(), U128(2_000_000), msg);
+
+        assert_eq!(contract.max_redeem(solver.clone()).0, 2_000_000_000);
+        assert_eq!(contract.get_locked_collateral(solver).0, 5_000_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Shares are locked as collateral")]
+    fn locked_shares_cannot_be_transferred() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        let solver: AccountId = "solver.test".parse().unwrap();
+        let other: AccountId = "alice.test".parse().unwrap();
+        contract.token.internal_register_account(&solver);
+        contract.token.internal_register_account(&other);
+
+        deposit_collateral(&mut contract, &solver, 5_000_000);
+        init_ctx("solver.test", 1);
+        contract.ft_transfer(other, U128(1), None);
+    }
+
+    #[test]
+    fn owner_release_unlocks_collateral() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        let solver: AccountId = "solver.test".parse().unwrap();
+        contract.token.internal_register_account(&solver);
+
+        deposit_collateral(&mut contract, &solver, 5_000_000);
+        init_ctx("owner.test", 0);
+        contract.release_collateral(solver.clone(), U128(5_000_000_000));
+
+        assert_eq!(contract.get_locked_collateral(solver.clone()).0, 0);
+        assert_eq!(contract.max_redeem(solver).0, 5_000_000_000);
+    }
+}
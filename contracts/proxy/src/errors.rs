@@ -0,0 +1,434 @@
+//! # Errors Module
+//!
+//! Errors that lenders, solvers and keepers can run into, each with a stable
+//! code. The panic string is always `"<CODE>: <message>"`, so integrators can
+//! match on the prefix while the message stays free to change.
+//!
+//! ## Usage
+//!
+//! ```ignore
+//! require!(shares > 0, VaultError::ZeroShares.to_string());
+//! VaultError::IntentNotFound.panic();
+//! ```
+//!
+//! Admin-only validation and internal overflow checks keep plain messages.
+
+use crate::intents::State;
+use near_sdk::{env, AccountId};
+use std::fmt;
+
+/// A user-facing vault or intent error with a stable code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VaultError {
+    /// Deposit is below the minimum deposit amount.
+    BelowMinDeposit { amount: u128, minimum: u128 },
+    /// Deposit seeding an empty vault is below the first-deposit minimum.
+    BelowMinFirstDeposit { amount: u128, minimum: u128 },
+    /// Donation is below the minimum donation amount.
+    BelowMinDonation { amount: u128, minimum: u128 },
+    /// Deposit would push vault value over the cap.
+    DepositCapExceeded,
+    /// Redemption's asset value is below the minimum.
+    BelowMinRedeem { amount: u128, minimum: u128 },
+    /// Withdrawal amount is below the minimum.
+    BelowMinWithdraw { amount: u128, minimum: u128 },
+    /// Redemption would pay out less than the caller's `min_assets_out`.
+    SlippageExceeded { assets: u128, minimum: u128 },
+    /// A deposit, donation or redemption memo is longer than allowed.
+    MemoTooLong { len: usize, maximum: u32 },
+    /// The position is worth at least the minimum redemption, so not dust.
+    NotDust { assets: u128, minimum: u128 },
+    /// More shares requested than the owner can redeem.
+    ExceedsMaxRedeem,
+    /// More assets requested than the owner can withdraw.
+    ExceedsMaxWithdraw,
+    /// Shares to redeem are zero.
+    ZeroShares,
+    /// Repayment amount is zero.
+    ZeroRepayment,
+    /// The call needs at least 1 yoctoNEAR attached.
+    RequiresOneYocto,
+    /// Queuing a redemption or adding a share allowance needs a larger
+    /// storage deposit.
+    InsufficientStorageDeposit { required: u128 },
+    /// The owner already has an entry in the redemption queue.
+    AlreadyQueued,
+    /// A first-time agent registration needs a larger deposit.
+    AgentDepositRequired { required: u128 },
+    /// The agent changed its codehash too often in the current window.
+    CodehashChangeLimit { maximum: u32 },
+    /// The agent's codehash is longer than a hex-encoded SHA-256 digest.
+    CodehashTooLong { len: usize, maximum: usize },
+    /// The caller is not a registered agent.
+    AgentNotRegistered,
+    /// The receiver is not on the redemption receiver allowlist.
+    ReceiverNotAllowed { receiver: AccountId },
+    /// The share transfer receiver is on the share transfer blocklist.
+    ShareReceiverBlocked { receiver: AccountId },
+    /// The caller has no share account with the vault.
+    AccountNotRegistered { account: AccountId },
+    /// The spender's share allowance does not cover the redemption.
+    InsufficientAllowance { allowance: u128, requested: u128 },
+    /// The bridge withdrawal is below the chain's configured minimum.
+    BelowChainMinimum {
+        chain: String,
+        amount: u128,
+        minimum: u128,
+    },
+    /// The account's share balance would exceed the per-account limit.
+    AccountShareLimitExceeded {
+        account: AccountId,
+        balance: u128,
+        limit: u128,
+    },
+    /// A share transfer would leave the sender with a dust balance.
+    ShareDustRemainder { remaining: u128, minimum: u128 },
+    /// The solver is blocked from opening new intents.
+    SolverBlocked { solver: AccountId },
+    /// Only intents still in `StpLiquidityBorrowed` can be cancelled.
+    IntentNotCancellable { state: State },
+    /// The intent's cancellation window has passed.
+    CancelWindowElapsed,
+    /// Only keepers or the owner may process the queue.
+    NotKeeper,
+    /// A bridged redemption needs more idle assets than the vault holds.
+    InsufficientIdleAssets { assets: u128, available: u128 },
+    /// Split redemption weights do not add up to 10,000 basis points.
+    InvalidSplitWeights { total: u32 },
+    /// The vault has too few idle assets for the borrow.
+    InsufficientLiquidity,
+    /// The borrow would draw idle assets below `min_reserve`.
+    ReserveBreached { available: u128, reserve: u128 },
+    /// Borrowing is blocked while lenders wait in the queue.
+    RedemptionsPending,
+    /// Another borrow is waiting for its transfer callback.
+    BorrowInFlight,
+    /// A redemption was paid out too recently to borrow.
+    BorrowCooldown { remaining: u64 },
+    /// `intent_data` is longer than the configured maximum.
+    IntentDataTooLong { len: usize, maximum: u32 },
+    /// `user_deposit_hash` is longer than the configured maximum.
+    DepositHashTooLong { len: usize, maximum: u32 },
+    /// A committed `intent_data` hash is not 32 hex-encoded bytes.
+    InvalidDataCommitment,
+    /// An open intent already uses this deposit hash.
+    DuplicateIntentHash,
+    /// The borrow deposit address is the vault or its asset token.
+    InvalidDepositAddress { account_id: AccountId },
+    /// The solver already holds `max_intents_per_solver` open intents.
+    TooManyIntents { maximum: u32 },
+    /// The solver has no open intents.
+    SolverHasNoIntents,
+    /// The intent belongs to a different solver.
+    IntentNotOwned,
+    /// No intent exists at the given index.
+    IntentNotFound,
+    /// The intent was marked returned, so it no longer accepts repayment.
+    IntentNotRepayable { state: State },
+    /// Repayment is below principal plus solver fee.
+    RepaymentTooLow {
+        amount: u128,
+        minimum: u128,
+        principal: u128,
+        expected_yield: u128,
+    },
+    /// Batched update contains no entries.
+    EmptyBatch,
+    /// Batched update has more entries than allowed.
+    BatchTooLarge { size: usize, maximum: usize },
+    /// The `ft_transfer_call` message could not be parsed.
+    InvalidMessage,
+}
+
+impl VaultError {
+    /// Returns the stable, machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            VaultError::BelowMinDeposit { .. } => "E_BELOW_MIN_DEPOSIT",
+            VaultError::BelowMinFirstDeposit { .. } => "E_BELOW_MIN_FIRST_DEPOSIT",
+            VaultError::BelowMinDonation { .. } => "E_BELOW_MIN_DONATION",
+            VaultError::DepositCapExceeded => "E_DEPOSIT_CAP",
+            VaultError::BelowMinRedeem { .. } => "E_BELOW_MIN_REDEEM",
+            VaultError::BelowMinWithdraw { .. } => "E_BELOW_MIN_WITHDRAW",
+            VaultError::SlippageExceeded { .. } => "E_SLIPPAGE",
+            VaultError::MemoTooLong { .. } => "E_MEMO_TOO_LONG",
+            VaultError::NotDust { .. } => "E_NOT_DUST",
+            VaultError::ExceedsMaxRedeem => "E_EXCEEDS_MAX_REDEEM",
+            VaultError::ExceedsMaxWithdraw => "E_EXCEEDS_MAX_WITHDRAW",
+            VaultError::ZeroShares | VaultError::ZeroRepayment => "E_ZERO_AMOUNT",
+            VaultError::RequiresOneYocto => "E_ONE_YOCTO",
+            VaultError::InsufficientStorageDeposit { .. } => "E_STORAGE_DEPOSIT",
+            VaultError::AlreadyQueued => "E_ALREADY_QUEUED",
+            VaultError::AgentDepositRequired { .. } => "E_AGENT_DEPOSIT",
+            VaultError::CodehashChangeLimit { .. } => "E_CODEHASH_CHANGE_LIMIT",
+            VaultError::CodehashTooLong { .. } => "E_CODEHASH_TOO_LONG",
+            VaultError::AgentNotRegistered => "E_AGENT_NOT_REGISTERED",
+            VaultError::ReceiverNotAllowed { .. } => "E_RECEIVER_NOT_ALLOWED",
+            VaultError::ShareReceiverBlocked { .. } => "E_SHARE_RECEIVER_BLOCKED",
+            VaultError::AccountNotRegistered { .. } => "E_ACCOUNT_NOT_REGISTERED",
+            VaultError::InsufficientAllowance { .. } => "E_INSUFFICIENT_ALLOWANCE",
+            VaultError::BelowChainMinimum { .. } => "E_BELOW_CHAIN_MINIMUM",
+            VaultError::AccountShareLimitExceeded { .. } => "E_ACCOUNT_SHARE_LIMIT",
+            VaultError::ShareDustRemainder { .. } => "E_SHARE_DUST",
+            VaultError::SolverBlocked { .. } => "E_SOLVER_BLOCKED",
+            VaultError::IntentNotCancellable { .. } => "E_INTENT_NOT_CANCELLABLE",
+            VaultError::CancelWindowElapsed => "E_CANCEL_WINDOW_ELAPSED",
+            VaultError::NotKeeper => "E_NOT_KEEPER",
+            VaultError::InsufficientIdleAssets { .. } => "E_INSUFFICIENT_IDLE_ASSETS",
+            VaultError::InvalidSplitWeights { .. } => "E_INVALID_SPLIT_WEIGHTS",
+            VaultError::InsufficientLiquidity => "E_INSUFFICIENT_LIQUIDITY",
+            VaultError::ReserveBreached { .. } => "E_RESERVE_BREACHED",
+            VaultError::RedemptionsPending => "E_REDEMPTIONS_PENDING",
+            VaultError::BorrowInFlight => "E_BORROW_IN_FLIGHT",
+            VaultError::BorrowCooldown { .. } => "E_BORROW_COOLDOWN",
+            VaultError::IntentDataTooLong { .. } => "E_INTENT_DATA_TOO_LONG",
+            VaultError::DepositHashTooLong { .. } => "E_HASH_TOO_LONG",
+            VaultError::InvalidDataCommitment => "E_INVALID_DATA_COMMITMENT",
+            VaultError::DuplicateIntentHash => "E_DUPLICATE_HASH",
+            VaultError::InvalidDepositAddress { .. } => "E_INVALID_DEPOSIT_ADDRESS",
+            VaultError::TooManyIntents { .. } => "E_TOO_MANY_INTENTS",
+            VaultError::SolverHasNoIntents => "E_NO_INTENTS",
+            VaultError::IntentNotOwned => "E_INTENT_NOT_OWNED",
+            VaultError::IntentNotFound => "E_INTENT_NOT_FOUND",
+            VaultError::IntentNotRepayable { .. } => "E_INTENT_NOT_REPAYABLE",
+            VaultError::RepaymentTooLow { .. } => "E_REPAY_TOO_LOW",
+            VaultError::EmptyBatch => "E_EMPTY_BATCH",
+            VaultError::BatchTooLarge { .. } => "E_BATCH_TOO_LARGE",
+            VaultError::InvalidMessage => "E_INVALID_MSG",
+        }
+    }
+
+    /// Aborts the call with this error's panic string.
+    pub fn panic(&self) -> ! {
+        env::panic_str(&self.to_string())
+    }
+}
+
+impl fmt::Display for VaultError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.code())?;
+        match self {
+            VaultError::BelowMinDeposit { amount, minimum } => {
+                write!(f, "Deposit amount {} is below minimum {}", amount, minimum)
+            }
+            VaultError::BelowMinFirstDeposit { amount, minimum } => {
+                write!(f, "First deposit {} is below minimum {}", amount, minimum)
+            }
+            VaultError::BelowMinDonation { amount, minimum } => {
+                write!(f, "Donation amount {} is below minimum {}", amount, minimum)
+            }
+            VaultError::DepositCapExceeded => write!(f, "Deposit exceeds vault cap"),
+            VaultError::BelowMinRedeem { amount, minimum } => {
+                write!(
+                    f,
+                    "Redemption amount {} is below minimum {}",
+                    amount, minimum
+                )
+            }
+            VaultError::BelowMinWithdraw { amount, minimum } => {
+                write!(
+                    f,
+                    "Withdrawal amount {} is below minimum {}",
+                    amount, minimum
+                )
+            }
+            VaultError::SlippageExceeded { assets, minimum } => {
+                write!(
+                    f,
+                    "Redemption assets {} are below minimum {}",
+                    assets, minimum
+                )
+            }
+            VaultError::MemoTooLong { len, maximum } => {
+                write!(f, "Memo of {} bytes exceeds maximum {}", len, maximum)
+            }
+            VaultError::NotDust { assets, minimum } => write!(
+                f,
+                "Position worth {} assets is not below the minimum {}",
+                assets, minimum
+            ),
+            VaultError::ExceedsMaxRedeem => write!(f, "Exceeds max redeem"),
+            VaultError::ExceedsMaxWithdraw => write!(f, "Exceeds max withdraw"),
+            VaultError::ZeroShares => write!(f, "Shares must be greater than 0"),
+            VaultError::ZeroRepayment => write!(f, "Repayment amount must be positive"),
+            VaultError::RequiresOneYocto => {
+                write!(f, "Requires attached deposit of at least 1 yoctoNEAR")
+            }
+            VaultError::InsufficientStorageDeposit { required } => write!(
+                f,
+                "Requires a storage deposit of {} yoctoNEAR",
+                required
+            ),
+            VaultError::AlreadyQueued => {
+                write!(f, "Lender already has a redemption in the queue")
+            }
+            VaultError::AgentDepositRequired { required } => write!(
+                f,
+                "Registering an agent requires a deposit of {} yoctoNEAR",
+                required
+            ),
+            VaultError::CodehashChangeLimit { maximum } => write!(
+                f,
+                "Agent already changed its codehash {} times in this window",
+                maximum
+            ),
+            VaultError::CodehashTooLong { len, maximum } => write!(
+                f,
+                "Codehash of {} bytes exceeds maximum {}",
+                len, maximum
+            ),
+            VaultError::AgentNotRegistered => write!(f, "Caller is not a registered agent"),
+            VaultError::ReceiverNotAllowed { receiver } => {
+                write!(f, "Receiver {} is not allowed", receiver)
+            }
+            VaultError::ShareReceiverBlocked { receiver } => {
+                write!(f, "{} is blocked from receiving vault shares", receiver)
+            }
+            VaultError::AccountNotRegistered { account } => {
+                write!(f, "{} is not registered with the vault", account)
+            }
+            VaultError::InsufficientAllowance {
+                allowance,
+                requested,
+            } => write!(
+                f,
+                "Allowance of {} shares does not cover {} requested",
+                allowance, requested
+            ),
+            VaultError::BelowChainMinimum {
+                chain,
+                amount,
+                minimum,
+            } => write!(
+                f,
+                "Withdrawal of {} to {} is below the chain minimum {}",
+                amount, chain, minimum
+            ),
+            VaultError::AccountShareLimitExceeded {
+                account,
+                balance,
+                limit,
+            } => write!(
+                f,
+                "{} would hold {} shares, above the per-account limit {}",
+                account, balance, limit
+            ),
+            VaultError::ShareDustRemainder { remaining, minimum } => write!(
+                f,
+                "Transfer would leave {} shares, below the minimum balance {}; transfer all shares instead",
+                remaining, minimum
+            ),
+            VaultError::SolverBlocked { solver } => {
+                write!(f, "{} is blocked from borrowing", solver)
+            }
+            VaultError::IntentNotCancellable { state } => {
+                write!(f, "Intent in state {:?} cannot be cancelled", state)
+            }
+            VaultError::CancelWindowElapsed => {
+                write!(f, "Intent cancellation window has passed; repay it instead")
+            }
+            VaultError::NotKeeper => {
+                write!(f, "Only a keeper or the owner can process redemptions")
+            }
+            VaultError::InsufficientIdleAssets { assets, available } => write!(
+                f,
+                "Redemption of {} assets exceeds idle assets {}",
+                assets, available
+            ),
+            VaultError::InvalidSplitWeights { total } => {
+                write!(f, "Split weights sum to {} bps instead of 10000", total)
+            }
+            VaultError::InsufficientLiquidity => write!(f, "Insufficient assets for solver borrow"),
+            VaultError::ReserveBreached { available, reserve } => write!(
+                f,
+                "Only {} can be borrowed while keeping the {} reserve",
+                available, reserve
+            ),
+            VaultError::RedemptionsPending => {
+                write!(f, "Cannot borrow while redemptions are pending")
+            }
+            VaultError::BorrowInFlight => write!(f, "Another borrow is in flight"),
+            VaultError::BorrowCooldown { remaining } => write!(
+                f,
+                "Borrowing is blocked for {} more blocks after a redemption",
+                remaining
+            ),
+            VaultError::IntentDataTooLong { len, maximum } => write!(
+                f,
+                "Intent data of {} bytes exceeds maximum {}",
+                len, maximum
+            ),
+            VaultError::DepositHashTooLong { len, maximum } => write!(
+                f,
+                "Deposit hash of {} bytes exceeds maximum {}",
+                len, maximum
+            ),
+            VaultError::InvalidDataCommitment => write!(
+                f,
+                "Intent data hash must be a 64-character hex SHA-256 digest"
+            ),
+            VaultError::DuplicateIntentHash => write!(f, "Intent with this hash already exists"),
+            VaultError::InvalidDepositAddress { account_id } => {
+                write!(f, "{} cannot receive borrowed liquidity", account_id)
+            }
+            VaultError::TooManyIntents { maximum } => {
+                write!(
+                    f,
+                    "Solver already holds the maximum of {} open intents",
+                    maximum
+                )
+            }
+            VaultError::SolverHasNoIntents => write!(f, "Solver has no intents"),
+            VaultError::IntentNotOwned => write!(f, "Intent not owned by solver"),
+            VaultError::IntentNotFound => write!(f, "Intent not found"),
+            VaultError::IntentNotRepayable { state } => {
+                write!(f, "Intent in state {:?} cannot be repaid", state)
+            }
+            VaultError::RepaymentTooLow {
+                amount,
+                minimum,
+                principal,
+                expected_yield,
+            } => write!(
+                f,
+                "Repayment {} is less than minimum required {} (principal {} + yield {})",
+                amount, minimum, principal, expected_yield
+            ),
+            VaultError::EmptyBatch => write!(f, "No intent updates provided"),
+            VaultError::BatchTooLarge { size, maximum } => {
+                write!(f, "Batch of {} updates exceeds maximum {}", size, maximum)
+            }
+            VaultError::InvalidMessage => write!(f, "Invalid ft_on_transfer message"),
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_string_starts_with_code() {
+        let error = VaultError::RepaymentTooLow {
+            amount: 1_000,
+            minimum: 1_010,
+            principal: 1_000,
+            expected_yield: 10,
+        };
+        assert_eq!(
+            error.to_string(),
+            "E_REPAY_TOO_LOW: Repayment 1000 is less than minimum required 1010 \
+             (principal 1000 + yield 10)"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "E_INTENT_NOT_FOUND: Intent not found")]
+    fn panic_uses_display_string() {
+        VaultError::IntentNotFound.panic();
+    }
+}
@@ -0,0 +1,272 @@
+//! # Protocol Fees Module
+//!
+//! Routes a cut of solver repayment yield to a protocol treasury. The cut is
+//! taken from the yield only (never the principal) and is held in
+//! `accrued_protocol_fees`, outside `total_assets`, so lenders' share price
+//! only ever reflects their own part of the yield.
+//!
+//! ## Fee Flow
+//!
+//! 1. Owner sets `protocol_fee_bps` and a `treasury_id`
+//! 2. Each repayment credits `yield * protocol_fee_bps / 10_000` to
+//!    `accrued_protocol_fees` and the rest to `total_assets`
+//! 3. The owner or the treasury calls `claim_protocol_fees`, which transfers
+//!    the accrued amount to the treasury
+//!
+//! The solver's minimum repayment is unaffected: solvers always owe the full
+//! `solver_fee`, however it is split afterwards.
+
+use crate::vault_standards::events::ProtocolFeesClaimed;
+use crate::vault_standards::mul_div::{mul_div, Rounding};
+use crate::*;
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_sdk::{ext_contract, PromiseResult};
+
+/// Basis-point denominator for `protocol_fee_bps`.
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+/// Callback interface for protocol fee claims.
+#[allow(dead_code)]
+#[ext_contract(ext_self)]
+trait ExtContract {
+    fn on_protocol_fees_claimed(&mut self, treasury_id: AccountId, amount: U128) -> U128;
+}
+
+impl Contract {
+    /// Returns the treasury's cut of `gross_yield`, rounded down in favor of
+    /// lenders.
+    pub(crate) fn internal_protocol_fee(&self, gross_yield: u128) -> u128 {
+        mul_div(
+            gross_yield,
+            self.protocol_fee_bps as u128,
+            BPS_DENOMINATOR as u128,
+            Rounding::Down,
+        )
+    }
+}
+
+#[near]
+impl Contract {
+    /// Sets the share of repayment yield routed to the treasury.
+    ///
+    /// Only affects repayments made after the change.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocol_fee_bps` - Treasury cut in basis points (e.g., 1000 = 10%)
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `protocol_fee_bps` exceeds 10,000
+    pub fn set_protocol_fee_bps(&mut self, protocol_fee_bps: u16) {
+        self.require_owner();
+        require!(
+            protocol_fee_bps <= BPS_DENOMINATOR,
+            "Protocol fee cannot exceed 10000 bps"
+        );
+        self.protocol_fee_bps = protocol_fee_bps;
+    }
+
+    /// Sets the account that receives claimed protocol fees.
+    ///
+    /// # Arguments
+    ///
+    /// * `treasury_id` - The treasury account, or `None` to disable claims
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn set_treasury(&mut self, treasury_id: Option<AccountId>) {
+        self.require_owner();
+        self.treasury_id = treasury_id;
+    }
+
+    /// Returns the protocol fees accrued and not yet claimed.
+    pub fn get_accrued_protocol_fees(&self) -> U128 {
+        U128(self.accrued_protocol_fees)
+    }
+
+    /// Transfers all accrued protocol fees to the treasury.
+    ///
+    /// The accumulator is cleared before the transfer and restored by
+    /// `on_protocol_fees_claimed` if it fails. Fees never enter
+    /// `total_assets`, so a claim cannot reach into lender assets.
+    ///
+    /// # Returns
+    ///
+    /// A promise resolving to the amount claimed (0 if the transfer failed).
+    ///
+    /// # Panics
+    ///
+    /// - If caller is neither the owner nor the treasury
+    /// - If no treasury is set
+    /// - If no fees have accrued
+    pub fn claim_protocol_fees(&mut self) -> Promise {
+        let treasury_id = self
+            .treasury_id
+            .clone()
+            .unwrap_or_else(|| env::panic_str("No treasury set"));
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.owner_id || caller == treasury_id,
+            "Only the owner or treasury can claim protocol fees"
+        );
+        let amount = self.accrued_protocol_fees;
+        require!(amount > 0, "No protocol fees to claim");
+
+        self.accrued_protocol_fees = 0;
+
+        ext_ft_core::ext(self.asset.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(self.gas_config.withdraw_transfer)
+            .ft_transfer(
+                treasury_id.clone(),
+                U128(amount),
+                Some("Protocol fees".to_string()),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(self.gas_config.withdraw_callback)
+                    .on_protocol_fees_claimed(treasury_id, U128(amount)),
+            )
+    }
+
+    /// Callback after transferring protocol fees to the treasury.
+    ///
+    /// Emits `ProtocolFeesClaimed` on success and restores the accrued
+    /// balance if the transfer failed.
+    #[private]
+    pub fn on_protocol_fees_claimed(&mut self, treasury_id: AccountId, amount: U128) -> U128 {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                ProtocolFeesClaimed {
+                    treasury_id: &treasury_id,
+                    amount,
+                }
+                .emit(self.event_standard());
+                amount
+            }
+            _ => {
+                self.accrued_protocol_fees = self
+                    .accrued_protocol_fees
+                    .checked_add(amount.0)
+                    .expect("accrued_protocol_fees overflow");
+                U128(0)
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::builders::ContractBuilder;
+    use crate::test_utils::helpers::{init_callback_ctx, init_ctx};
+    use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+    use near_sdk::test_utils::{get_created_receipts, get_logs};
+
+    /// Builds a vault with a 1_000_000 borrow open and the given fee split,
+    /// then repays `amount` for it.
+    fn repay_with_protocol_fee(protocol_fee_bps: u16, amount: u128) -> Contract {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(1_000_000)
+            .intent("solver.test", "h", 1_000_000)
+            .build();
+        init_ctx("owner.test", 0);
+        contract.set_protocol_fee_bps(protocol_fee_bps);
+        contract.set_treasury(Some("treasury.test".parse().unwrap()));
+
+        let solver: AccountId = "solver.test".parse().unwrap();
+        init_ctx("usdc.test", 0);
+        let msg = serde_json::json!({ "repay": { "intent_index": "0" } }).to_string();
+        let _ = contract.ft_on_transfer(solver, U128(amount), msg);
+        contract
+    }
+
+    #[test]
+    fn repayment_splits_yield_between_lenders_and_treasury() {
+        // 20% of the 10_000 yield goes to the treasury
+        let contract = repay_with_protocol_fee(2_000, 1_010_000);
+        assert_eq!(contract.get_accrued_protocol_fees(), U128(2_000));
+        assert_eq!(contract.total_assets, 1_008_000);
+        assert_eq!(contract.total_borrowed, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_REPAY_TOO_LOW")]
+    fn protocol_fee_does_not_lower_minimum_repayment() {
+        // Even with the whole yield going to the treasury, solvers owe 1%
+        repay_with_protocol_fee(BPS_DENOMINATOR, 1_009_999);
+    }
+
+    #[test]
+    fn treasury_claim_transfers_accrued_fees() {
+        let mut contract = repay_with_protocol_fee(2_000, 1_010_000);
+
+        init_ctx("treasury.test", 0);
+        let _ = contract.claim_protocol_fees();
+        assert_eq!(contract.get_accrued_protocol_fees(), U128(0));
+        // Lender assets are untouched by the claim
+        assert_eq!(contract.total_assets, 1_008_000);
+
+        let transfer = get_created_receipts()
+            .into_iter()
+            .find(|receipt| receipt.receiver_id.as_str() == "usdc.test")
+            .expect("ft_transfer receipt");
+        match &transfer.actions[0] {
+            near_sdk::mock::MockAction::FunctionCallWeight {
+                method_name, args, ..
+            } => {
+                assert_eq!(method_name, b"ft_transfer");
+                let args: serde_json::Value = serde_json::from_slice(args).unwrap();
+                assert_eq!(args["receiver_id"], "treasury.test");
+                assert_eq!(args["amount"], "2000");
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+    }
+
+    #[test]
+    fn successful_claim_emits_event() {
+        let mut contract = repay_with_protocol_fee(2_000, 1_010_000);
+        init_ctx("owner.test", 0);
+        let _ = contract.claim_protocol_fees();
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        let claimed =
+            contract.on_protocol_fees_claimed("treasury.test".parse().unwrap(), U128(2_000));
+        assert_eq!(claimed, U128(2_000));
+        assert_eq!(contract.get_accrued_protocol_fees(), U128(0));
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains("\"protocol_fees_claimed\"")
+                && log.contains("treasury.test")
+                && log.contains("\"2000\"")));
+    }
+
+    #[test]
+    fn failed_claim_restores_accrued_fees() {
+        let mut contract = repay_with_protocol_fee(2_000, 1_010_000);
+        init_ctx("owner.test", 0);
+        let _ = contract.claim_protocol_fees();
+
+        init_callback_ctx(PromiseResult::Failed);
+        let claimed =
+            contract.on_protocol_fees_claimed("treasury.test".parse().unwrap(), U128(2_000));
+        assert_eq!(claimed, U128(0));
+        assert_eq!(contract.get_accrued_protocol_fees(), U128(2_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner or treasury can claim protocol fees")]
+    fn stranger_cannot_claim_protocol_fees() {
+        let mut contract = repay_with_protocol_fee(2_000, 1_010_000);
+        init_ctx("alice.test", 0);
+        let _ = contract.claim_protocol_fees();
+    }
+}
@@ -0,0 +1,183 @@
+//! # Gas Configuration Module
+//!
+//! Static gas attached to the vault's asset transfers and their callbacks.
+//! The defaults fit a plain NEP-141 token; assets whose `ft_transfer` does
+//! extra work (transfer hooks, storage bookkeeping) may need more, so the
+//! owner can raise them.
+//!
+//! ## Allocations
+//!
+//! | Field               | Used for                                    | Default |
+//! | ------------------- | ------------------------------------------- | ------- |
+//! | `withdraw_transfer` | `ft_transfer` paying out a withdrawal       | 30 TGas |
+//! | `withdraw_callback` | `resolve_withdraw`                          | 10 TGas |
+//! | `borrow_transfer`   | `ft_transfer` sending a borrow to a solver  | 30 TGas |
+//! | `borrow_callback`   | `on_new_intent_callback`                    | 8 TGas  |
+//!
+//! Every allocation must lie within `MIN_STATIC_GAS..=MAX_STATIC_GAS`. A
+//! starved callback could not roll back a failed transfer, and an oversized
+//! one would leave too little of a 300 TGas call for the rest of the work.
+
+use crate::*;
+
+/// Smallest static gas accepted for any allocation.
+pub const MIN_STATIC_GAS: Gas = Gas::from_tgas(5);
+
+/// Largest static gas accepted for any allocation.
+pub const MAX_STATIC_GAS: Gas = Gas::from_tgas(100);
+
+/// Static gas attached to asset transfers and their callbacks.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasConfig {
+    /// Gas for the asset `ft_transfer` paying out a withdrawal.
+    pub withdraw_transfer: Gas,
+    /// Gas for the `resolve_withdraw` callback.
+    pub withdraw_callback: Gas,
+    /// Gas for the asset `ft_transfer` sending borrowed liquidity.
+    pub borrow_transfer: Gas,
+    /// Gas for the `on_new_intent_callback` callback.
+    pub borrow_callback: Gas,
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            withdraw_transfer: Gas::from_tgas(30),
+            withdraw_callback: Gas::from_tgas(10),
+            borrow_transfer: Gas::from_tgas(30),
+            borrow_callback: Gas::from_tgas(8),
+        }
+    }
+}
+
+impl GasConfig {
+    /// Asserts that every allocation lies within the accepted range.
+    ///
+    /// # Panics
+    ///
+    /// Panics naming the first allocation outside `MIN_STATIC_GAS..=MAX_STATIC_GAS`.
+    fn assert_valid(&self) {
+        for (name, gas) in [
+            ("withdraw_transfer", self.withdraw_transfer),
+            ("withdraw_callback", self.withdraw_callback),
+            ("borrow_transfer", self.borrow_transfer),
+            ("borrow_callback", self.borrow_callback),
+        ] {
+            require!(
+                (MIN_STATIC_GAS..=MAX_STATIC_GAS).contains(&gas),
+                format!(
+                    "Gas for {} must be between {} and {} TGas",
+                    name,
+                    MIN_STATIC_GAS.as_tgas(),
+                    MAX_STATIC_GAS.as_tgas()
+                )
+            );
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Replaces the static gas allocations (owner-only).
+    ///
+    /// # Arguments
+    ///
+    /// * `gas_config` - The new allocations
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If any allocation is outside `MIN_STATIC_GAS..=MAX_STATIC_GAS`
+    pub fn set_gas_config(&mut self, gas_config: GasConfig) {
+        self.require_owner();
+        gas_config.assert_valid();
+        self.gas_config = gas_config;
+    }
+
+    /// Returns the static gas allocations.
+    pub fn get_gas_config(&self) -> GasConfig {
+        self.gas_config
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::builders::ContractBuilder;
+    use crate::test_utils::helpers::init_ctx;
+    use near_sdk::json_types::U128;
+    use near_sdk::mock::MockAction;
+    use near_sdk::test_utils::get_created_receipts;
+
+    /// Returns the prepaid gas of the first function call named `method`.
+    fn prepaid_gas_for(method: &str) -> Gas {
+        get_created_receipts()
+            .into_iter()
+            .flat_map(|receipt| receipt.actions)
+            .find_map(|action| match action {
+                MockAction::FunctionCallWeight {
+                    method_name,
+                    prepaid_gas,
+                    ..
+                } if method_name == method.as_bytes() => Some(prepaid_gas),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no {} call", method))
+    }
+
+    #[test]
+    #[should_panic(expected = "Gas for borrow_callback must be between 5 and 100 TGas")]
+    fn set_gas_config_rejects_starved_callback() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        init_ctx("owner.test", 0);
+        contract.set_gas_config(GasConfig {
+            borrow_callback: Gas::from_tgas(1),
+            ..GasConfig::default()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gas for withdraw_transfer must be between 5 and 100 TGas")]
+    fn set_gas_config_rejects_oversized_transfer() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        init_ctx("owner.test", 0);
+        contract.set_gas_config(GasConfig {
+            withdraw_transfer: Gas::from_tgas(250),
+            ..GasConfig::default()
+        });
+    }
+
+    #[test]
+    fn configured_gas_is_attached_to_borrow() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        let gas_config = GasConfig {
+            borrow_transfer: Gas::from_tgas(45),
+            borrow_callback: Gas::from_tgas(12),
+            ..GasConfig::default()
+        };
+        init_ctx("owner.test", 0);
+        contract.set_gas_config(gas_config);
+        assert_eq!(contract.get_gas_config(), gas_config);
+        assert_eq!(contract.get_vault_config().gas, gas_config);
+
+        init_ctx("solver.test", 0);
+        contract.new_intent(
+            "intent".to_string(),
+            "solver.deposit".parse().unwrap(),
+            "hash-1".to_string(),
+            U128(1_000_000),
+        );
+        assert_eq!(prepaid_gas_for("ft_transfer"), Gas::from_tgas(45));
+        assert_eq!(
+            prepaid_gas_for("on_new_intent_callback"),
+            Gas::from_tgas(12)
+        );
+    }
+}
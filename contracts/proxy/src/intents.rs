@@ -10,24 +10,73 @@
 //! 3. **Processing**: Solver executes the cross-chain swap
 //! 4. **Repaid**: Solver returns liquidity with yield
 //!
+//! ## Committed Intent Data
+//!
+//! `new_intent_committed` records only the SHA-256 of the intent payload,
+//! hex-encoded, in `intent_data` and sets `data_committed`. The payload stays
+//! off-chain; anyone holding it can check it with `verify_intent_data`.
+//! Committed hashes are a fixed 32 bytes and are not subject to
+//! `max_intent_data_len`.
+//!
+//! ## Concurrency Model
+//!
+//! `new_intent` deducts `total_assets` before the `ft_transfer` to the solver
+//! resolves, and only `on_new_intent_callback` records the intent or rolls the
+//! deduction back. Only one borrow may be in that window at a time: the
+//! `borrow_in_flight` flag is set by `new_intent` and cleared by the callback,
+//! and any other `new_intent` landing in between is rejected. This keeps the
+//! duplicate-hash and liquidity checks from running against half-applied state.
+//!
 //! ## Yield Model
 //!
 //! Solvers must repay principal + 1% yield when returning borrowed funds.
 //! This yield is distributed to lenders proportionally to their shares.
+//!
+//! An optional origination fee (`origination_fee_bps`) is kept back from the
+//! borrow transfer itself: the solver receives `amount - fee`, the fee never
+//! leaves `total_assets`, and the intent's principal is still the full
+//! `amount`. The repayment minimum is therefore unchanged by the fee.
 
+use crate::fees::BPS_DENOMINATOR;
+use crate::vault_standards::events::{
+    BorrowFailed, IntentCreated, IntentStateChanged, IntentsCleared, LossRealized,
+};
+use crate::vault_standards::mul_div::{mul_div, Rounding};
 use crate::*;
-use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_contract_standards::fungible_token::core::{ext_ft_core, FungibleTokenCore};
 use near_sdk::{
     env, ext_contract,
     json_types::{U128, U64},
-    Gas, NearToken, Promise, PromiseResult,
+    NearToken, Promise, PromiseResult,
 };
 
-/// Gas allocation for the solver borrow `ft_transfer`.
-const GAS_FOR_SOLVER_BORROW: Gas = Gas::from_tgas(30);
+/// Fixed-point scale for share prices (assets per share * 1e18).
+const SHARE_PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Default number of repaid intents kept in the completed intent history.
+pub const DEFAULT_HISTORY_CAPACITY: u32 = 20;
+
+/// Number of failed borrow attempts kept in the failed borrow log.
+pub const MAX_FAILED_BORROWS: u32 = 20;
 
-/// Gas allocation for the `on_new_intent_callback`.
-const GAS_FOR_NEW_INTENT_CALLBACK: Gas = Gas::from_tgas(8);
+/// Reason recorded when the borrow `ft_transfer` to the deposit address fails.
+const BORROW_TRANSFER_FAILED: &str =
+    "ft_transfer to the deposit address failed (is it registered with the asset?)";
+
+/// Largest accepted `origination_fee_bps` (10%).
+pub const MAX_ORIGINATION_FEE_BPS: u16 = 1_000;
+
+/// Maximum number of updates accepted by `update_intent_states`.
+pub const MAX_INTENT_STATE_BATCH: usize = 50;
+
+/// Default cap on `intent_data` length, in bytes.
+pub const DEFAULT_MAX_INTENT_DATA_LEN: u32 = 1024;
+
+/// Default cap on `user_deposit_hash` length, in bytes.
+pub const DEFAULT_MAX_HASH_LEN: u32 = 128;
+
+/// Default window after a borrow in which the solver may cancel (10 minutes).
+pub const DEFAULT_INTENT_CANCEL_WINDOW_NS: u64 = 10 * 60 * 1_000_000_000;
 
 /// External contract interface for callback methods.
 #[allow(dead_code)]
@@ -39,6 +88,8 @@ trait ExtContract {
         solver_id: AccountId,
         user_deposit_hash: String,
         amount: U128,
+        fees: BorrowFees,
+        data_committed: bool,
     ) -> bool;
 }
 
@@ -47,8 +98,12 @@ trait ExtContract {
 // ============================================================================
 
 /// Represents the current state of an intent in its lifecycle.
+///
+/// Solvers advance an intent through these states with
+/// `update_intent_state` while its principal is out. Any state except
+/// `StpLiquidityReturned` can be repaid; see [`State::is_repayable`].
 #[near(serializers = [json, borsh])]
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum State {
     /// Liquidity has been borrowed from the vault by the solver.
     StpLiquidityBorrowed,
@@ -68,6 +123,28 @@ pub enum State {
     StpLiquidityReturned,
 }
 
+impl State {
+    /// Whether an intent in this state still accepts its repayment.
+    ///
+    /// Repayment moves an intent to `StpLiquidityReturned` and out of
+    /// storage. An open intent found in that state was marked returned by
+    /// its solver without the vault being paid, which only
+    /// `write_off_intent` can resolve.
+    pub fn is_repayable(&self) -> bool {
+        *self != State::StpLiquidityReturned
+    }
+}
+
+/// Fee terms a borrow was opened with, handed to its transfer callback.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowFees {
+    /// Part of the borrow amount kept back from the transfer.
+    pub origination_fee: U128,
+    /// Solver fee percentage in force when the borrow started.
+    pub solver_fee: u8,
+}
+
 /// Represents a solver's intent to fulfill a cross-chain swap.
 #[near(serializers = [json, borsh])]
 #[derive(Clone)]
@@ -76,12 +153,18 @@ pub struct Intent {
     pub created: U64,
     /// Current state in the intent lifecycle.
     pub state: State,
-    /// Serialized intent data (quote details, destination, etc.).
+    /// Serialized intent data (quote details, destination, etc.), or the
+    /// hex SHA-256 of it when `data_committed` is set.
     pub intent_data: String,
+    /// Whether `intent_data` is a hash commitment to an off-chain payload.
+    #[serde(default)]
+    pub data_committed: bool,
     /// Hash of the user's deposit transaction for verification.
     pub user_deposit_hash: String,
     /// Amount of liquidity borrowed from the vault (principal).
     pub borrow_amount: U128,
+    /// Solver fee percentage in force when the liquidity was borrowed.
+    pub solver_fee: u8,
     /// Repayment amount when liquidity is returned (principal + yield).
     pub repayment_amount: Option<U128>,
 }
@@ -96,130 +179,724 @@ pub struct IndexedIntent {
     pub intent: Intent,
 }
 
+/// A repaid intent kept in the completed intent history.
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct CompletedIntent {
+    /// The intent index in the contract.
+    pub index: U128,
+    /// The solver that borrowed and repaid the intent.
+    pub solver_id: AccountId,
+    /// Unix timestamp when the intent was repaid.
+    pub completed: U64,
+    /// The intent in its final state, including the repayment amount.
+    pub intent: Intent,
+}
+
+/// A borrow whose transfer to the solver failed and was rolled back.
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct FailedBorrow {
+    /// The solver that requested the borrow.
+    pub solver_id: AccountId,
+    /// The amount that was requested.
+    pub amount: U128,
+    /// Hash of the user's deposit the intent was for.
+    pub user_deposit_hash: String,
+    /// What failed. The runtime does not expose the receiver's panic
+    /// message, so this names the failed call and its most common causes.
+    pub reason: String,
+    /// Unix timestamp of the callback that saw the failure.
+    pub failed_at: U64,
+}
+
+/// Outcome of a simulated `new_intent` borrow.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowPreview {
+    /// Whether `new_intent` would accept the borrow right now.
+    pub allowed: bool,
+    /// The error `new_intent` would panic with, if any.
+    pub reason: Option<String>,
+    /// Idle assets available to borrow, after the `min_reserve` floor.
+    pub available: U128,
+}
+
+/// Simulated effect of an open intent defaulting.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefaultImpact {
+    /// Borrowed principal that would never be returned.
+    pub principal_lost: U128,
+    /// Share price after the write-off, in assets per share scaled by 1e18.
+    pub share_price_after: U128,
+}
+
 // ============================================================================
-// Contract Implementation
+// Origination Fee
+// ============================================================================
+
+impl Contract {
+    /// Returns the origination fee kept from a borrow of `borrow_amount`,
+    /// rounded down in favor of the solver.
+    pub(crate) fn internal_origination_fee(&self, borrow_amount: u128) -> u128 {
+        mul_div(
+            borrow_amount,
+            self.origination_fee_bps as u128,
+            BPS_DENOMINATOR as u128,
+            Rounding::Down,
+        )
+    }
+}
+
+// ============================================================================
+// Completed Intent History
+// ============================================================================
+
+impl Contract {
+    /// Appends a repaid intent to the history, evicting the oldest entry
+    /// once `history_capacity` is reached.
+    pub(crate) fn record_completed_intent(
+        &mut self,
+        index: u128,
+        solver_id: AccountId,
+        intent: Intent,
+    ) {
+        if self.history_capacity == 0 {
+            return;
+        }
+        let entry = CompletedIntent {
+            index: U128(index),
+            solver_id,
+            completed: U64(env::block_timestamp()),
+            intent,
+        };
+
+        let len = self.completed_intents.len();
+        if len < self.history_capacity {
+            self.completed_intents.push(entry);
+        } else {
+            self.completed_intents
+                .replace(self.completed_intents_cursor, entry);
+            self.completed_intents_cursor = (self.completed_intents_cursor + 1) % len;
+        }
+    }
+
+    /// Returns the completed intent history, oldest first.
+    fn ordered_completed_intents(&self) -> impl Iterator<Item = &CompletedIntent> {
+        let len = self.completed_intents.len();
+        (0..len).filter_map(move |position| {
+            self.completed_intents
+                .get((self.completed_intents_cursor + position) % len)
+        })
+    }
+}
+
 // ============================================================================
+// Failed Borrow Log
+// ============================================================================
+
+impl Contract {
+    /// Appends a failed borrow to the log, overwriting the oldest entry once
+    /// `MAX_FAILED_BORROWS` is reached.
+    fn record_failed_borrow(&mut self, entry: FailedBorrow) {
+        let len = self.failed_borrows.len();
+        if len < MAX_FAILED_BORROWS {
+            self.failed_borrows.push(entry);
+        } else {
+            self.failed_borrows
+                .replace(self.failed_borrows_cursor, entry);
+            self.failed_borrows_cursor = (self.failed_borrows_cursor + 1) % len;
+        }
+    }
+}
 
 #[near]
 impl Contract {
-    /// Creates a new intent and borrows liquidity from the vault.
+    /// Sets how many repaid intents the history keeps.
     ///
-    /// This is the entry point for solvers to start fulfilling a swap.
-    /// The solver receives borrowed liquidity which they must repay with yield.
+    /// Shrinking the capacity drops the oldest entries; `0` disables the history.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn set_history_capacity(&mut self, capacity: u32) {
+        self.require_owner();
+
+        let kept: Vec<CompletedIntent> = self.ordered_completed_intents().cloned().collect();
+        let skip = kept.len().saturating_sub(capacity as usize);
+        self.completed_intents.clear();
+        self.completed_intents.extend(kept.into_iter().skip(skip));
+        self.completed_intents_cursor = 0;
+        self.history_capacity = capacity;
+    }
+
+    /// Sets how many blocks borrows stay blocked after a redemption payout.
+    ///
+    /// Every redemption or withdrawal that pays out (immediately or from the
+    /// queue) restarts the window. `0` disables the cooldown.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn set_borrow_cooldown_blocks(&mut self, blocks: u64) {
+        self.require_owner();
+        self.borrow_cooldown_blocks = blocks;
+    }
+
+    /// Sets the byte-length limits on `new_intent`'s stored strings.
     ///
     /// # Arguments
     ///
-    /// * `intent_data` - Serialized intent/quote details
-    /// * `_solver_deposit_address` - Reserved for future use
-    /// * `user_deposit_hash` - Hash of user's deposit for verification
-    /// * `amount` - Amount of liquidity to borrow from the vault
+    /// * `max_intent_data_len` - Maximum length of `intent_data`
+    /// * `max_hash_len` - Maximum length of `user_deposit_hash`
     ///
     /// # Panics
     ///
-    /// - If an intent with the same `user_deposit_hash` already exists
-    /// - If there are pending redemptions in the queue
-    /// - If the vault has insufficient assets
-    pub fn new_intent(
+    /// - If caller is not the contract owner
+    /// - If either limit is zero
+    pub fn set_intent_size_limits(&mut self, max_intent_data_len: u32, max_hash_len: u32) {
+        self.require_owner();
+        require!(
+            max_intent_data_len > 0 && max_hash_len > 0,
+            "Intent size limits must be greater than 0"
+        );
+        self.max_intent_data_len = max_intent_data_len;
+        self.max_hash_len = max_hash_len;
+    }
+
+    /// Sets how many open intents a single solver may hold at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_intents` - Per-solver limit, or `None` for no limit
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `max_intents` is `Some(0)`
+    pub fn set_max_intents_per_solver(&mut self, max_intents: Option<u32>) {
+        self.require_owner();
+        require!(
+            max_intents != Some(0),
+            "Max intents per solver must be greater than 0"
+        );
+        self.max_intents_per_solver = max_intents;
+    }
+
+    /// Sets how long after borrowing a solver may cancel an intent.
+    ///
+    /// Within the window a `cancel_repay` transfer of the bare principal
+    /// closes the intent without the solver fee. `0` disables cancellation.
+    ///
+    /// # Arguments
+    ///
+    /// * `window_ns` - Cancellation window in nanoseconds
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn set_intent_cancel_window(&mut self, window_ns: U64) {
+        self.require_owner();
+        self.intent_cancel_window_ns = window_ns.0;
+    }
+
+    /// Sets the fee kept from each new borrow, in basis points.
+    ///
+    /// The fee stays in the vault as lender yield; the solver still owes
+    /// the full borrowed amount plus the solver fee.
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `origination_fee_bps` exceeds `MAX_ORIGINATION_FEE_BPS`
+    pub fn set_origination_fee_bps(&mut self, origination_fee_bps: u16) {
+        self.require_owner();
+        require!(
+            origination_fee_bps <= MAX_ORIGINATION_FEE_BPS,
+            format!(
+                "Origination fee cannot exceed {} bps",
+                MAX_ORIGINATION_FEE_BPS
+            )
+        );
+        self.origination_fee_bps = origination_fee_bps;
+    }
+
+    /// Returns the number of open intents held by `solver_id`.
+    pub fn get_solver_intent_count(&self, solver_id: AccountId) -> u32 {
+        self.solver_id_to_indices
+            .get(&solver_id)
+            .map_or(0, |indices| indices.len() as u32)
+    }
+
+    /// Returns how many more blocks must pass before `new_intent` is accepted.
+    ///
+    /// # Returns
+    ///
+    /// `0` once `block_height >= last_redemption_block + borrow_cooldown_blocks`.
+    pub fn get_borrow_cooldown_remaining(&self) -> u64 {
+        self.last_redemption_block
+            .saturating_add(self.borrow_cooldown_blocks)
+            .saturating_sub(env::block_height())
+    }
+
+    /// Reports whether `new_intent` would accept a borrow of `amount`.
+    ///
+    /// Runs the same pause, queue, cooldown and liquidity checks as
+    /// `new_intent` without changing state. Per-intent checks (data length,
+    /// duplicate hash) are not covered.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - Amount the solver intends to borrow
+    ///
+    /// # Returns
+    ///
+    /// A `BorrowPreview` with the rejection reason, if any, and idle assets.
+    pub fn preview_borrow(&self, amount: U128) -> BorrowPreview {
+        let reason = self.borrow_block_reason(amount.0);
+        BorrowPreview {
+            allowed: reason.is_none(),
+            reason,
+            available: U128(self.internal_available_to_borrow()),
+        }
+    }
+
+    /// Returns how much can be borrowed right now: idle assets above the
+    /// `min_reserve` floor.
+    ///
+    /// Does not account for pauses, the queue or the cooldown; use
+    /// `preview_borrow` for the full check.
+    pub fn get_available_to_borrow(&self) -> U128 {
+        U128(self.internal_available_to_borrow())
+    }
+
+    /// Sets the idle assets that borrows may never draw below.
+    ///
+    /// The reserve keeps small redemptions instantly payable whatever the
+    /// borrow demand. It only limits new borrows; redemptions can still use
+    /// it.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_reserve` - Absolute floor on `total_assets` after a borrow
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn set_min_reserve(&mut self, min_reserve: U128) {
+        self.require_owner();
+        self.min_reserve = min_reserve.0;
+    }
+
+    /// Returns the most recent failed borrow attempts, oldest first.
+    ///
+    /// At most `MAX_FAILED_BORROWS` entries are kept.
+    pub fn get_failed_borrows(&self) -> Vec<FailedBorrow> {
+        let len = self.failed_borrows.len();
+        (0..len)
+            .filter_map(|position| {
+                self.failed_borrows
+                    .get((self.failed_borrows_cursor + position) % len)
+                    .cloned()
+            })
+            .collect()
+    }
+
+    /// Returns repaid intents from the history, oldest first, with optional pagination.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_index` - Starting position for pagination (default: 0)
+    /// * `limit` - Maximum number of entries to return (default: all)
+    pub fn get_completed_intents(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<CompletedIntent> {
+        self.ordered_completed_intents()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.history_capacity) as usize)
+            .cloned()
+            .collect()
+    }
+}
+
+// ============================================================================
+// Borrow Checks
+// ============================================================================
+
+impl Contract {
+    /// Returns why a borrow of `amount` would be rejected right now, or
+    /// `None` if it would go through.
+    ///
+    /// Shared by `new_intent` (which panics with the reason) and
+    /// `preview_borrow` (which reports it), so the two never disagree.
+    pub(crate) fn borrow_block_reason(&self, amount: u128) -> Option<String> {
+        if self.pause_flags.contains(PauseFlag::Borrows) {
+            return Some(format!("{:?} are paused", PauseFlag::Borrows));
+        }
+        if self.borrow_in_flight {
+            return Some(VaultError::BorrowInFlight.to_string());
+        }
+        // Block borrowing while lenders are waiting for redemptions
+        if self.pending_redemptions_head < self.pending_redemptions.len() {
+            return Some(VaultError::RedemptionsPending.to_string());
+        }
+        // Give lenders a window to redeem again before liquidity leaves
+        let remaining = self.get_borrow_cooldown_remaining();
+        if remaining > 0 {
+            return Some(VaultError::BorrowCooldown { remaining }.to_string());
+        }
+        if self.total_assets < amount {
+            return Some(VaultError::InsufficientLiquidity.to_string());
+        }
+        let available = self.internal_available_to_borrow();
+        if amount > available {
+            return Some(
+                VaultError::ReserveBreached {
+                    available,
+                    reserve: self.min_reserve,
+                }
+                .to_string(),
+            );
+        }
+        None
+    }
+
+    /// Returns the idle assets a borrow may take without touching the
+    /// `min_reserve` floor.
+    pub(crate) fn internal_available_to_borrow(&self) -> u128 {
+        self.total_assets.saturating_sub(self.min_reserve)
+    }
+
+    /// Runs the `new_intent` checks for `solver_id` and starts the borrow.
+    ///
+    /// Shared by `new_intent`, `new_intent_committed` and the
+    /// `deposit_then_borrow` transfer action. `data_committed` marks
+    /// `intent_data` as an already validated hash commitment. Returns the
+    /// transfer promise, chained to `on_new_intent_callback`; the caller
+    /// decides whether to return it.
+    pub(crate) fn internal_new_intent(
         &mut self,
+        solver_id: AccountId,
         intent_data: String,
-        _solver_deposit_address: AccountId,
+        data_committed: bool,
+        solver_deposit_address: AccountId,
         user_deposit_hash: String,
         amount: U128,
-    ) {
-        self.require_not_paused();
+    ) -> Promise {
+        if self.blocked_solvers.contains(&solver_id) {
+            VaultError::SolverBlocked { solver: solver_id }.panic();
+        }
+        // Both strings end up in storage, so bound them before anything else
+        require!(
+            data_committed || intent_data.len() <= self.max_intent_data_len as usize,
+            VaultError::IntentDataTooLong {
+                len: intent_data.len(),
+                maximum: self.max_intent_data_len,
+            }
+            .to_string()
+        );
+        require!(
+            user_deposit_hash.len() <= self.max_hash_len as usize,
+            VaultError::DepositHashTooLong {
+                len: user_deposit_hash.len(),
+                maximum: self.max_hash_len,
+            }
+            .to_string()
+        );
         // Prevent duplicate intents for the same user deposit
-        if self
-            .index_to_intent
-            .values()
-            .any(|intent| intent.user_deposit_hash == user_deposit_hash)
+        if self.hash_to_intent_index.contains_key(&user_deposit_hash) {
+            VaultError::DuplicateIntentHash.panic();
+        }
+        // Neither account can take part in a solver borrow
+        if solver_deposit_address == env::current_account_id()
+            || solver_deposit_address == self.asset
         {
-            env::panic_str("Intent with this hash already exists");
+            VaultError::InvalidDepositAddress {
+                account_id: solver_deposit_address,
+            }
+            .panic();
         }
 
-        let solver_id = env::predecessor_account_id();
         let borrow_amount = amount.0;
 
-        // Block borrowing while lenders are waiting for redemptions
-        require!(
-            self.pending_redemptions_head >= self.pending_redemptions.len(),
-            "Cannot borrow while redemptions are pending"
-        );
+        if let Some(maximum) = self.max_intents_per_solver {
+            require!(
+                self.get_solver_intent_count(solver_id.clone()) < maximum,
+                VaultError::TooManyIntents { maximum }.to_string()
+            );
+        }
 
-        // Verify sufficient liquidity
-        require!(
-            self.total_assets >= borrow_amount,
-            "Insufficient assets for solver borrow"
-        );
+        if let Some(reason) = self.borrow_block_reason(borrow_amount) {
+            env::panic_str(&reason);
+        }
+
+        // The origination fee never leaves the vault, so only the net
+        // transfer is deducted from total_assets
+        let origination_fee = self.internal_origination_fee(borrow_amount);
+        let net_amount = borrow_amount - origination_fee;
 
-        // Deduct from available assets (optimistic update)
+        // Deduct from available assets (optimistic update). Until the callback
+        // confirms the transfer, the principal is tracked as pending so vault
+        // value stays whole for any deposit or redemption in between.
         self.total_assets = self
             .total_assets
-            .checked_sub(borrow_amount)
+            .checked_sub(net_amount)
             .expect("total_assets underflow");
+        self.pending_borrowed = self
+            .pending_borrowed
+            .checked_add(borrow_amount)
+            .expect("pending_borrowed overflow");
+        self.borrow_in_flight = true;
 
         // =====================================================================
         // Cross-Contract Call: Transfer Borrowed Liquidity to Solver
         // =====================================================================
-        // Transfers the borrowed amount from the vault to the solver.
+        // Transfers the borrowed amount from the vault to the solver's
+        // deposit address.
         // The callback `on_new_intent_callback` records the intent on success
         // or rolls back the total_assets deduction on failure.
         // =====================================================================
-        let promise: Promise = ext_ft_core::ext(self.asset.clone())
+        ext_ft_core::ext(self.asset.clone())
             .with_attached_deposit(NearToken::from_yoctonear(1))
-            .with_static_gas(GAS_FOR_SOLVER_BORROW)
+            .with_static_gas(self.gas_config.borrow_transfer)
             .ft_transfer(
-                solver_id.clone(),
-                U128(borrow_amount),
+                solver_deposit_address,
+                U128(net_amount),
                 Some("Solver borrow".to_string()),
             )
             .then(
                 ext_self::ext(env::current_account_id())
-                    .with_static_gas(GAS_FOR_NEW_INTENT_CALLBACK)
+                    .with_static_gas(self.gas_config.borrow_callback)
                     .on_new_intent_callback(
                         intent_data,
                         solver_id,
                         user_deposit_hash,
                         U128(borrow_amount),
+                        BorrowFees {
+                            origination_fee: U128(origination_fee),
+                            solver_fee: self.solver_fee,
+                        },
+                        data_committed,
                     ),
-            );
+            )
+    }
 
-        let _ = promise.as_return();
+    /// Parses a committed `intent_data` hash into its lowercase hex form.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `intent_data_hash` is not 32 hex-encoded bytes.
+    fn parse_data_commitment(intent_data_hash: &str) -> String {
+        let mut digest = [0u8; 32];
+        if hex::decode_to_slice(intent_data_hash, &mut digest).is_err() {
+            VaultError::InvalidDataCommitment.panic();
+        }
+        hex::encode(digest)
     }
+}
 
-    /// Callback after attempting to transfer borrowed liquidity.
+// ============================================================================
+// Contract Implementation
+// ============================================================================
+
+#[near]
+impl Contract {
+    /// Creates a new intent and borrows liquidity from the vault.
     ///
-    /// Records the intent on success or rolls back state on failure.
-    #[private]
-    pub fn on_new_intent_callback(
+    /// This is the entry point for solvers to start fulfilling a swap.
+    /// The borrowed liquidity is sent to `solver_deposit_address`, while the
+    /// intent (and the obligation to repay it with yield) belongs to the
+    /// caller. This lets a solver's hot wallet borrow on behalf of its
+    /// settlement account.
+    ///
+    /// The deposit address must be registered with the asset token; if it is
+    /// not, the transfer fails and the borrow is rolled back and recorded in
+    /// `get_failed_borrows`.
+    ///
+    /// With an origination fee set, the deposit address receives `amount`
+    /// minus the fee, but the intent records (and the solver repays) the
+    /// full `amount`.
+    ///
+    /// # Arguments
+    ///
+    /// * `intent_data` - Serialized intent/quote details
+    /// * `solver_deposit_address` - Account that receives the borrowed liquidity
+    /// * `user_deposit_hash` - Hash of user's deposit for verification
+    /// * `amount` - Amount of liquidity to borrow from the vault
+    ///
+    /// # Panics
+    ///
+    /// - If borrows are paused
+    /// - If `intent_data` or `user_deposit_hash` exceeds its configured length
+    /// - If `solver_deposit_address` is the vault or the asset token
+    /// - If another borrow is still awaiting its callback
+    /// - If an intent with the same `user_deposit_hash` already exists
+    /// - If the solver already holds `max_intents_per_solver` open intents
+    /// - If there are pending redemptions in the queue
+    /// - If a redemption paid out within the last `borrow_cooldown_blocks`
+    /// - If the vault has insufficient assets
+    /// - If the borrow would draw idle assets below `min_reserve`
+    pub fn new_intent(
         &mut self,
         intent_data: String,
-        solver_id: AccountId,
+        solver_deposit_address: AccountId,
         user_deposit_hash: String,
         amount: U128,
-    ) -> bool {
-        match env::promise_result(0) {
-            PromiseResult::Successful(_) => {
-                self.insert_intent(solver_id, intent_data, user_deposit_hash, amount);
-                true
-            }
-            _ => {
-                // Rollback: restore the deducted assets
-                self.total_assets = self
+    ) {
+        let solver_id = env::predecessor_account_id();
+        let promise = self.internal_new_intent(
+            solver_id,
+            intent_data,
+            false,
+            solver_deposit_address,
+            user_deposit_hash,
+            amount,
+        );
+        let _ = promise.as_return();
+    }
+
+    /// Creates a new intent that stores only a hash of its intent data.
+    ///
+    /// Behaves like `new_intent`, but the payload stays off-chain: the
+    /// intent records `intent_data_hash` and is marked `data_committed`.
+    /// Use `verify_intent_data` to check a payload against it later.
+    ///
+    /// # Arguments
+    ///
+    /// * `intent_data_hash` - Hex-encoded SHA-256 of the intent data
+    /// * `solver_deposit_address` - Account that receives the borrowed liquidity
+    /// * `user_deposit_hash` - Hash of user's deposit for verification
+    /// * `amount` - Amount of liquidity to borrow from the vault
+    ///
+    /// # Panics
+    ///
+    /// - If `intent_data_hash` is not 32 hex-encoded bytes
+    /// - In every case `new_intent` panics, except the `intent_data` length
+    pub fn new_intent_committed(
+        &mut self,
+        intent_data_hash: String,
+        solver_deposit_address: AccountId,
+        user_deposit_hash: String,
+        amount: U128,
+    ) {
+        let solver_id = env::predecessor_account_id();
+        let commitment = Self::parse_data_commitment(&intent_data_hash);
+        let promise = self.internal_new_intent(
+            solver_id,
+            commitment,
+            true,
+            solver_deposit_address,
+            user_deposit_hash,
+            amount,
+        );
+        let _ = promise.as_return();
+    }
+
+    /// Checks `data` against an open intent's intent data.
+    ///
+    /// For a committed intent, `data` is hashed with SHA-256 and compared to
+    /// the stored commitment; otherwise it is compared to the stored data.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The intent index
+    /// * `data` - The claimed intent data
+    ///
+    /// # Returns
+    ///
+    /// `true` if `data` matches the intent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no open intent exists at `index`.
+    pub fn verify_intent_data(&self, index: U128, data: String) -> bool {
+        let intent = self
+            .index_to_intent
+            .get(&index.0)
+            .unwrap_or_else(|| VaultError::IntentNotFound.panic());
+        if intent.data_committed {
+            hex::encode(env::sha256_array(data.as_bytes())) == intent.intent_data
+        } else {
+            data == intent.intent_data
+        }
+    }
+
+    /// Callback after attempting to transfer borrowed liquidity.
+    ///
+    /// Records the intent on success. On failure, rolls back state and logs
+    /// the attempt in `failed_borrows`. `fees` carries the part of `amount`
+    /// that was kept back from the transfer and the solver fee the intent
+    /// is recorded with.
+    #[private]
+    pub fn on_new_intent_callback(
+        &mut self,
+        intent_data: String,
+        solver_id: AccountId,
+        user_deposit_hash: String,
+        amount: U128,
+        fees: BorrowFees,
+        data_committed: bool,
+    ) -> bool {
+        self.borrow_in_flight = false;
+        self.pending_borrowed = self
+            .pending_borrowed
+            .checked_sub(amount.0)
+            .expect("pending_borrowed underflow");
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                self.insert_intent(
+                    solver_id,
+                    intent_data,
+                    user_deposit_hash,
+                    amount,
+                    data_committed,
+                    fees.solver_fee,
+                );
+                #[cfg(test)]
+                self.assert_accounting_consistent();
+                true
+            }
+            _ => {
+                // Rollback: restore the deducted assets
+                self.total_assets = self
                     .total_assets
-                    .checked_add(amount.0)
+                    .checked_add(amount.0 - fees.origination_fee.0)
                     .expect("total_assets overflow on borrow revert");
+
+                BorrowFailed {
+                    solver_id: &solver_id,
+                    amount,
+                    user_deposit_hash: &user_deposit_hash,
+                }
+                .emit(self.event_standard());
+                self.record_failed_borrow(FailedBorrow {
+                    solver_id,
+                    amount,
+                    user_deposit_hash,
+                    reason: BORROW_TRANSFER_FAILED.to_string(),
+                    failed_at: U64(env::block_timestamp()),
+                });
+
                 false
             }
         }
     }
 
     /// Records a new intent after successful liquidity transfer.
-    fn insert_intent(
+    ///
+    /// `solver_fee` is kept on the intent, so a later `set_solver_fee`
+    /// changes neither its minimum repayment nor its expected yield.
+    pub(crate) fn insert_intent(
         &mut self,
         solver_id: AccountId,
         intent_data: String,
         user_deposit_hash: String,
         borrow_amount: U128,
+        data_committed: bool,
+        solver_fee: u8,
     ) {
         let index = self.intent_nonce;
         self.intent_nonce += 1;
@@ -236,27 +913,95 @@ impl Contract {
             .total_borrowed
             .checked_add(borrow_amount.0)
             .expect("total_borrowed overflow");
+        self.open_intent_fees = self
+            .open_intent_fees
+            .checked_add(Self::internal_solver_fee(borrow_amount.0, solver_fee))
+            .expect("open_intent_fees overflow");
+
+        let created = U64(env::block_timestamp());
+        IntentCreated {
+            solver_id: &solver_id,
+            intent_index: U128(index),
+            borrow_amount,
+            user_deposit_hash: &user_deposit_hash,
+            created,
+            total_borrowed: U128(self.total_borrowed),
+            total_assets: U128(self.total_assets),
+        }
+        .emit(self.event_standard());
 
+        self.hash_to_intent_index
+            .insert(user_deposit_hash.clone(), index);
         self.index_to_intent.insert(
             index,
             Intent {
-                created: U64(env::block_timestamp()),
+                created,
                 state: State::StpLiquidityBorrowed,
                 intent_data,
+                data_committed,
                 user_deposit_hash,
                 borrow_amount,
+                solver_fee,
                 repayment_amount: None,
             },
         );
     }
 
+    /// Clears the in-flight borrow flag (owner-only).
+    ///
+    /// Escape hatch for a borrow whose callback never ran, which would
+    /// otherwise block `new_intent` for good. Only use it once the borrow's
+    /// receipts have fully settled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn reset_borrow_in_flight(&mut self) {
+        self.require_owner();
+        self.borrow_in_flight = false;
+    }
+
     /// Clears all intents (owner-only, for debugging).
-    pub fn clear_intents(&mut self) {
-        self.require_not_paused();
+    ///
+    /// By default the contract must be fully paused and no liquidity may be
+    /// borrowed, since wiping a borrowed intent writes off its principal.
+    /// `force` skips both checks; the `IntentsCleared` event then records
+    /// how much principal was abandoned.
+    ///
+    /// # Arguments
+    ///
+    /// * `force` - Clear even while unpaused or with outstanding borrows
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - Without `force`, if the contract is not fully paused
+    /// - Without `force`, if any liquidity is still borrowed
+    pub fn clear_intents(&mut self, force: Option<bool>) {
         self.require_owner();
+        let force = force.unwrap_or(false);
+        if !force {
+            require!(
+                self.pause_flags.is_all(),
+                "Contract must be paused to clear intents"
+            );
+            require!(
+                self.total_borrowed == 0,
+                "Cannot clear intents with outstanding borrows"
+            );
+        }
+
+        IntentsCleared {
+            intents_cleared: self.index_to_intent.len(),
+            abandoned_principal: U128(self.total_borrowed),
+            forced: force,
+        }
+        .emit(self.event_standard());
         self.solver_id_to_indices.clear();
         self.index_to_intent.clear();
+        self.hash_to_intent_index.clear();
         self.total_borrowed = 0;
+        self.open_intent_fees = 0;
     }
 
     /// Returns intents in the contract with their indices, with optional pagination.
@@ -271,7 +1016,7 @@ impl Contract {
     /// A vector of indexed intents within the specified range.
     pub fn get_intents(&self, from_index: Option<u32>, limit: Option<u32>) -> Vec<IndexedIntent> {
         let from = from_index.unwrap_or(0) as usize;
-        let limit = limit.unwrap_or(self.index_to_intent.len() as u32) as usize;
+        let limit = limit.unwrap_or(self.index_to_intent.len()) as usize;
 
         self.index_to_intent
             .iter()
@@ -284,6 +1029,64 @@ impl Contract {
             .collect()
     }
 
+    /// Looks up an open intent by the hash of the user's deposit.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_deposit_hash` - Hash the intent was created with
+    ///
+    /// # Returns
+    ///
+    /// The intent with its index, or `None` if no open intent uses the hash.
+    pub fn get_intent_by_hash(&self, user_deposit_hash: String) -> Option<IndexedIntent> {
+        let index = *self.hash_to_intent_index.get(&user_deposit_hash)?;
+        self.index_to_intent
+            .get(&index)
+            .map(|intent| IndexedIntent {
+                index: U128(index),
+                intent: intent.clone(),
+            })
+    }
+
+    /// Returns the index of the open intent created for a user deposit.
+    ///
+    /// Lets integrators address an intent by its `user_deposit_hash` without
+    /// fetching the whole intent.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_deposit_hash` - Hash the intent was created with
+    ///
+    /// # Returns
+    ///
+    /// The intent index, or `None` if no open intent uses the hash.
+    pub fn hash_to_index(&self, user_deposit_hash: String) -> Option<U128> {
+        self.hash_to_intent_index
+            .get(&user_deposit_hash)
+            .map(|index| U128(*index))
+    }
+
+    /// Updates the state of an intent addressed by its deposit hash.
+    ///
+    /// Same as `update_intent_state`, including the ownership check.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_deposit_hash` - Hash the intent was created with
+    /// * `state` - The new state to set
+    ///
+    /// # Panics
+    ///
+    /// - If no open intent uses the hash
+    /// - If the caller doesn't own the intent
+    pub fn update_intent_state_by_hash(&mut self, user_deposit_hash: String, state: State) {
+        let index = *self
+            .hash_to_intent_index
+            .get(&user_deposit_hash)
+            .unwrap_or_else(|| VaultError::IntentNotFound.panic());
+        self.update_intent_state(index, state);
+    }
+
     /// Updates the state of an intent.
     ///
     /// Only the solver who owns the intent can update its state.
@@ -298,13 +1101,26 @@ impl Contract {
     /// - If the caller doesn't own the intent
     /// - If the intent doesn't exist
     pub fn update_intent_state(&mut self, index: u128, state: State) {
-        self.require_not_paused();
+        self.require_not_paused_for(PauseFlag::Borrows);
         let solver_id = env::predecessor_account_id();
-        let indices = self.get_intent_indices(solver_id);
+        let indices = self.get_intent_indices(solver_id.clone());
 
-        require!(indices.contains(&index), "Intent not owned by solver");
-        let intent = self.index_to_intent.get(&index).expect("Intent not found");
+        require!(
+            indices.contains(&index),
+            VaultError::IntentNotOwned.to_string()
+        );
+        let intent = self
+            .index_to_intent
+            .get(&index)
+            .unwrap_or_else(|| VaultError::IntentNotFound.panic());
 
+        IntentStateChanged {
+            solver_id: &solver_id,
+            intent_index: U128(index),
+            old_state: &intent.state,
+            new_state: &state,
+        }
+        .emit(self.event_standard());
         self.index_to_intent.insert(
             index,
             Intent {
@@ -314,6 +1130,66 @@ impl Contract {
         );
     }
 
+    /// Updates the state of several intents in one call.
+    ///
+    /// Every index is checked before anything is written, so the batch is
+    /// applied in full or not at all.
+    ///
+    /// # Arguments
+    ///
+    /// * `updates` - Pairs of intent index and the new state to set
+    ///
+    /// # Panics
+    ///
+    /// - If the batch is empty or larger than `MAX_INTENT_STATE_BATCH`
+    /// - If the caller doesn't own every intent in the batch
+    /// - If any intent doesn't exist
+    pub fn update_intent_states(&mut self, updates: Vec<(u128, State)>) {
+        self.require_not_paused_for(PauseFlag::Borrows);
+        require!(!updates.is_empty(), VaultError::EmptyBatch.to_string());
+        require!(
+            updates.len() <= MAX_INTENT_STATE_BATCH,
+            VaultError::BatchTooLarge {
+                size: updates.len(),
+                maximum: MAX_INTENT_STATE_BATCH
+            }
+            .to_string()
+        );
+
+        let solver_id = env::predecessor_account_id();
+        let indices = self.get_intent_indices(solver_id.clone());
+        let intents: Vec<Intent> = updates
+            .iter()
+            .map(|(index, _)| {
+                require!(
+                    indices.contains(index),
+                    VaultError::IntentNotOwned.to_string()
+                );
+                self.index_to_intent
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_else(|| VaultError::IntentNotFound.panic())
+            })
+            .collect();
+
+        let events: Vec<IntentStateChanged> = updates
+            .iter()
+            .zip(&intents)
+            .map(|((index, state), intent)| IntentStateChanged {
+                solver_id: &solver_id,
+                intent_index: U128(*index),
+                old_state: &intent.state,
+                new_state: state,
+            })
+            .collect();
+        IntentStateChanged::emit_many(&events, self.event_standard());
+
+        for ((index, state), intent) in updates.into_iter().zip(intents) {
+            self.index_to_intent
+                .insert(index, Intent { state, ..intent });
+        }
+    }
+
     /// Returns intents owned by a specific solver with optional pagination.
     ///
     /// # Arguments
@@ -348,28 +1224,321 @@ impl Contract {
             .collect()
     }
 
+    /// Returns intents in a given state with optional pagination.
+    ///
+    /// Intents are not indexed by state, so this scans `index_to_intent` in
+    /// order and filters as it goes. Pagination counts matching intents only;
+    /// keep `limit` small on vaults with many open intents.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to match
+    /// * `from_index` - Number of matching intents to skip (default: 0)
+    /// * `limit` - Maximum number of intents to return (default: all)
+    ///
+    /// # Returns
+    ///
+    /// A vector of matching intents within the specified range.
+    pub fn get_intents_by_state(
+        &self,
+        state: State,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<IndexedIntent> {
+        let from = from_index.unwrap_or(0) as usize;
+        let limit = limit.unwrap_or(self.index_to_intent.len()) as usize;
+
+        self.index_to_intent
+            .iter()
+            .filter(|(_, intent)| intent.state == state)
+            .skip(from)
+            .take(limit)
+            .map(|(index, intent)| IndexedIntent {
+                index: U128(*index),
+                intent: intent.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the solver's open intents that a single budget can repay.
+    ///
+    /// Walks the solver's repayable intents in ascending index order and
+    /// includes each one whose minimum repayment still fits in the remaining
+    /// budget. Useful for planning batched repayments.
+    ///
+    /// # Arguments
+    ///
+    /// * `solver_id` - The solver's account ID
+    /// * `amount` - Total amount available for repayment
+    ///
+    /// # Returns
+    ///
+    /// The indices of the intents covered by `amount`.
+    pub fn repayable_within(&self, solver_id: AccountId, amount: U128) -> Vec<U128> {
+        let mut indices = self
+            .solver_id_to_indices
+            .get(&solver_id)
+            .cloned()
+            .unwrap_or_default();
+        indices.sort_unstable();
+
+        let mut remaining = amount.0;
+        let mut covered = Vec::new();
+        for index in indices {
+            let Some(intent) = self.index_to_intent.get(&index) else {
+                continue;
+            };
+            if !intent.state.is_repayable() {
+                continue;
+            }
+            let (minimum_repayment, _) = self.internal_minimum_repayment(intent);
+            if minimum_repayment <= remaining {
+                remaining -= minimum_repayment;
+                covered.push(U128(index));
+            }
+        }
+        covered
+    }
+
+    /// Returns the total a solver must repay to close all its borrowed intents.
+    ///
+    /// Sums the minimum repayment (principal plus solver fee) of each of the
+    /// solver's repayable intents.
+    ///
+    /// # Arguments
+    ///
+    /// * `solver_id` - The solver's account ID
+    ///
+    /// # Returns
+    ///
+    /// The total owed, or 0 if the solver has no borrowed intents.
+    pub fn get_solver_total_owed(&self, solver_id: AccountId) -> U128 {
+        let indices = self
+            .solver_id_to_indices
+            .get(&solver_id)
+            .cloned()
+            .unwrap_or_default();
+        let total = indices
+            .iter()
+            .filter_map(|index| self.index_to_intent.get(index))
+            .filter(|intent| intent.state.is_repayable())
+            .fold(0u128, |sum, intent| {
+                let (minimum_repayment, _) = self.internal_minimum_repayment(intent);
+                sum.saturating_add(minimum_repayment)
+            });
+        U128(total)
+    }
+
+    /// Returns the exact minimum repayment accepted for an intent.
+    ///
+    /// Uses the same formula as the repayment check, so repaying this
+    /// amount is never rejected as too small.
+    ///
+    /// # Arguments
+    ///
+    /// * `intent_index` - The intent index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the intent does not exist.
+    pub fn get_minimum_repayment(&self, intent_index: U128) -> U128 {
+        let intent = self
+            .index_to_intent
+            .get(&intent_index.0)
+            .unwrap_or_else(|| VaultError::IntentNotFound.panic());
+        let (minimum_repayment, _) = self.internal_minimum_repayment(intent);
+        U128(minimum_repayment)
+    }
+
+    /// Simulates the write-off of an open intent without mutating state.
+    ///
+    /// The borrowed principal is removed from `total_borrowed` with no
+    /// assets returned, and the share price is recomputed the same way
+    /// `convert_to_assets` values shares (including expected yield on the
+    /// remaining borrows).
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The intent index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the intent does not exist.
+    pub fn default_impact(&self, index: U128) -> DefaultImpact {
+        let intent = self
+            .index_to_intent
+            .get(&index.0)
+            .unwrap_or_else(|| VaultError::IntentNotFound.panic());
+        let principal_lost = intent.borrow_amount.0;
+
+        let total_borrowed =
+            (self.total_borrowed + self.pending_borrowed).saturating_sub(principal_lost);
+        let open_intent_fees = self
+            .open_intent_fees
+            .saturating_sub(Self::internal_solver_fee(principal_lost, intent.solver_fee));
+        let expected_yield = self.expected_yield_for(total_borrowed, open_intent_fees);
+        let total_value = self.total_assets + total_borrowed + expected_yield;
+
+        DefaultImpact {
+            principal_lost: U128(principal_lost),
+            share_price_after: U128(self.internal_share_price(total_value)),
+        }
+    }
+
+    /// Returns the share price, in assets per share scaled by 1e18.
+    ///
+    /// Values shares the way `convert_to_assets` does: idle assets plus
+    /// outstanding borrows and their expected lender yield. Written-off
+    /// principal is no longer part of it.
+    pub fn get_exchange_rate(&self) -> U128 {
+        U128(self.internal_share_price(self.get_tvl_with_expected_yield().0))
+    }
+
+    /// Returns the cumulative principal written off by `write_off_intent`.
+    pub fn get_total_written_off(&self) -> U128 {
+        U128(self.total_written_off)
+    }
+
+    /// Writes off a defaulted intent whose principal will never return.
+    ///
+    /// Removes the intent and subtracts its `borrow_amount` from
+    /// `total_borrowed` without crediting `total_assets`, so the loss and
+    /// the expected yield it no longer earns leave the share price at once.
+    /// The loss is added to `total_written_off` and a `LossRealized` event
+    /// is emitted. Use `default_impact` to preview the effect. Redemptions
+    /// already queued are paid at most their post-loss value when processed.
+    ///
+    /// An intent that can still be repaid (see [`State::is_repayable`])
+    /// needs `force`; one its solver marked `StpLiquidityReturned` without
+    /// paying does not.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The intent index
+    /// * `force` - Write off even if the intent is still repayable
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If the intent does not exist
+    /// - Without `force`, if the intent is still repayable
+    pub fn write_off_intent(&mut self, index: U128, force: Option<bool>) {
+        self.require_owner();
+        let intent = self
+            .index_to_intent
+            .remove(&index.0)
+            .unwrap_or_else(|| VaultError::IntentNotFound.panic());
+        let repayable = intent.state.is_repayable();
+        require!(
+            !repayable || force.unwrap_or(false),
+            "Intent is still repayable; pass force to write it off"
+        );
+
+        let principal_lost = intent.borrow_amount.0;
+        self.total_borrowed = self
+            .total_borrowed
+            .checked_sub(principal_lost)
+            .expect("total_borrowed underflow");
+        self.internal_release_intent_fee(&intent);
+        self.total_written_off = self
+            .total_written_off
+            .checked_add(principal_lost)
+            .expect("total_written_off overflow");
+        self.hash_to_intent_index.remove(&intent.user_deposit_hash);
+
+        let solver_id = self
+            .solver_id_to_indices
+            .iter()
+            .find(|(_, indices)| indices.contains(&index.0))
+            .map(|(solver_id, _)| solver_id.clone())
+            .unwrap_or_else(|| env::panic_str("Intent has no owning solver"));
+        let mut indices = self.get_intent_indices(solver_id.clone());
+        indices.retain(|&idx| idx != index.0);
+        if indices.is_empty() {
+            self.solver_id_to_indices.remove(&solver_id);
+        } else {
+            self.solver_id_to_indices.insert(solver_id.clone(), indices);
+        }
+
+        LossRealized {
+            solver_id: &solver_id,
+            intent_index: index,
+            principal_lost: U128(principal_lost),
+            total_written_off: U128(self.total_written_off),
+            forced: repayable,
+        }
+        .emit(self.event_standard());
+    }
+
+    /// Converts a total vault value into a share price scaled by 1e18.
+    fn internal_share_price(&self, total_value: u128) -> u128 {
+        let total_supply = self.token.ft_total_supply().0;
+        if total_supply == 0 {
+            SHARE_PRICE_SCALE / 10u128.pow(self.extra_decimals as u32)
+        } else {
+            mul_div(total_value, SHARE_PRICE_SCALE, total_supply, Rounding::Down)
+        }
+    }
+
     /// Returns the intent indices for a solver.
     fn get_intent_indices(&self, solver_id: AccountId) -> Vec<u128> {
         self.solver_id_to_indices
             .get(&solver_id)
-            .expect("No intents for solver")
+            .unwrap_or_else(|| VaultError::SolverHasNoIntents.panic())
             .to_vec()
     }
 }
 
 // ============================================================================
-// Unit Tests
+// Solver Blocklist
 // ============================================================================
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::test_utils::builders::ContractBuilder;
-    use crate::test_utils::helpers::init_ctx as init_account;
+#[near]
+impl Contract {
+    /// Bars a solver from opening new intents.
+    ///
+    /// The solver's open intents are untouched: it can still update and
+    /// repay them, so the borrowed funds can be recovered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn block_solver(&mut self, account_id: AccountId) {
+        self.require_owner();
+        self.blocked_solvers.insert(account_id);
+    }
 
-    #[test]
-    #[should_panic(expected = "Insufficient assets for solver borrow")]
-    fn new_intent_fails_when_assets_insufficient() {
+    /// Lets a blocked solver borrow again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn unblock_solver(&mut self, account_id: AccountId) {
+        self.require_owner();
+        self.blocked_solvers.remove(&account_id);
+    }
+
+    /// Returns whether `account_id` is blocked from borrowing.
+    pub fn is_solver_blocked(&self, account_id: AccountId) -> bool {
+        self.blocked_solvers.contains(&account_id)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::builders::ContractBuilder;
+    use crate::test_utils::helpers::{init_callback_ctx, init_ctx as init_account};
+    use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    #[test]
+    #[should_panic(expected = "E_INSUFFICIENT_LIQUIDITY: Insufficient assets for solver borrow")]
+    fn new_intent_fails_when_assets_insufficient() {
         let mut contract = ContractBuilder::new("owner.test", "usdc.test")
             .total_assets(1_000_000)
             .predecessor("solver.test")
@@ -383,6 +1552,264 @@ mod tests {
         );
     }
 
+    #[test]
+    fn preview_borrow_allows_within_liquidity() {
+        let contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        assert_eq!(
+            contract.preview_borrow(U128(10_000_000)),
+            BorrowPreview {
+                allowed: true,
+                reason: None,
+                available: U128(10_000_000),
+            }
+        );
+    }
+
+    #[test]
+    fn preview_borrow_reports_insufficient_liquidity() {
+        let contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        let preview = contract.preview_borrow(U128(10_000_001));
+        assert!(!preview.allowed);
+        assert_eq!(
+            preview.reason,
+            Some(VaultError::InsufficientLiquidity.to_string())
+        );
+        assert_eq!(preview.available, U128(10_000_000));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "E_RESERVE_BREACHED: Only 8000000 can be borrowed while keeping the 2000000 reserve"
+    )]
+    fn new_intent_rejects_borrow_into_reserve() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        contract.set_min_reserve(U128(2_000_000));
+        init_account("solver.test", 1);
+        contract.new_intent(
+            "intent".to_string(),
+            "solver.deposit".parse().unwrap(),
+            "hash-1".to_string(),
+            U128(8_000_001),
+        );
+    }
+
+    #[test]
+    fn borrow_may_draw_down_to_reserve() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(3_000_000)
+            .build();
+        contract.set_min_reserve(U128(2_000_000));
+        assert_eq!(contract.get_available_to_borrow(), U128(1_000_000));
+        assert_eq!(
+            contract.preview_borrow(U128(1_000_000)).available,
+            U128(1_000_000)
+        );
+
+        borrow(&mut contract, "h-0");
+        assert_eq!(contract.total_assets, 2_000_000);
+        assert_eq!(contract.get_available_to_borrow(), U128(0));
+        assert!(!contract.preview_borrow(U128(1)).allowed);
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_min_reserve_requires_owner() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .predecessor("alice.test")
+            .build();
+        contract.set_min_reserve(U128(1));
+    }
+
+    #[test]
+    fn preview_borrow_blocked_by_queue_without_side_effects() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        let lender: AccountId = "alice.test".parse().unwrap();
+        contract.pending_redemptions.push(crate::PendingRedemption {
+            owner_id: lender.clone(),
+            receiver_id: lender,
+            shares: 1,
+            assets: 20_000_000,
+            memo: None,
+            storage_deposit: 0,
+            min_assets_out: None,
+        });
+
+        let preview = contract.preview_borrow(U128(1_000_000));
+        assert!(!preview.allowed);
+        assert_eq!(
+            preview.reason,
+            Some(VaultError::RedemptionsPending.to_string())
+        );
+        assert_eq!(contract.total_assets, 10_000_000);
+        assert!(!contract.borrow_in_flight);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_REDEMPTIONS_PENDING: ")]
+    fn new_intent_blocked_by_queued_redemption() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .predecessor("solver.test")
+            .attached(1)
+            .build();
+        let lender: AccountId = "alice.test".parse().unwrap();
+        contract.pending_redemptions.push(crate::PendingRedemption {
+            owner_id: lender.clone(),
+            receiver_id: lender,
+            shares: 1,
+            assets: 20_000_000,
+            memo: None,
+            storage_deposit: 0,
+            min_assets_out: None,
+        });
+        contract.new_intent(
+            "intent".to_string(),
+            "solver.deposit".parse().unwrap(),
+            "hash-1".to_string(),
+            U128(1_000_000),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "E_EMPTY_BATCH: ")]
+    fn empty_intent_state_batch_has_error_code() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .predecessor("solver.test")
+            .build();
+        contract.update_intent_states(vec![]);
+    }
+
+    #[test]
+    fn borrow_cooldown_follows_last_redemption() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        init_account("owner.test", 0);
+        contract.set_borrow_cooldown_blocks(10);
+        contract.last_redemption_block = 100;
+
+        let mut context = VMContextBuilder::new();
+        context.predecessor_account_id("solver.test".parse().unwrap());
+        context.block_height(104);
+        testing_env!(context.build());
+        assert_eq!(contract.get_borrow_cooldown_remaining(), 6);
+
+        context.block_height(110);
+        testing_env!(context.build());
+        assert_eq!(contract.get_borrow_cooldown_remaining(), 0);
+        contract.new_intent(
+            "intent".to_string(),
+            "solver.deposit".parse().unwrap(),
+            "hash-1".to_string(),
+            U128(1_000_000),
+        );
+        assert!(contract.borrow_in_flight);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_BORROW_COOLDOWN: Borrowing is blocked for 1 more blocks")]
+    fn borrow_rejected_during_cooldown() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        contract.borrow_cooldown_blocks = 10;
+        contract.last_redemption_block = 100;
+
+        let mut context = VMContextBuilder::new();
+        context.predecessor_account_id("solver.test".parse().unwrap());
+        context.block_height(109);
+        testing_env!(context.build());
+        contract.new_intent(
+            "intent".to_string(),
+            "solver.deposit".parse().unwrap(),
+            "hash-1".to_string(),
+            U128(1_000_000),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "E_INTENT_DATA_TOO_LONG: Intent data of 1025 bytes exceeds maximum 1024"
+    )]
+    fn new_intent_rejects_oversized_intent_data() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .predecessor("solver.test")
+            .build();
+        contract.new_intent(
+            "x".repeat(DEFAULT_MAX_INTENT_DATA_LEN as usize + 1),
+            "solver.deposit".parse().unwrap(),
+            "hash-1".to_string(),
+            U128(1_000_000),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "E_TOO_MANY_INTENTS: Solver already holds the maximum of 2 open intents"
+    )]
+    fn new_intent_rejects_solver_at_intent_limit() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .intent("solver.test", "h-0", 1_000)
+            .intent("solver.test", "h-1", 1_000)
+            .build();
+        init_account("owner.test", 0);
+        contract.set_max_intents_per_solver(Some(2));
+
+        let solver: AccountId = "solver.test".parse().unwrap();
+        assert_eq!(contract.get_solver_intent_count(solver), 2);
+        assert_eq!(
+            contract.get_solver_intent_count("other.test".parse().unwrap()),
+            0
+        );
+        borrow(&mut contract, "h-2");
+    }
+
+    #[test]
+    #[should_panic(expected = "E_HASH_TOO_LONG: Deposit hash of 129 bytes exceeds maximum 128")]
+    fn new_intent_rejects_oversized_hash() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .predecessor("solver.test")
+            .build();
+        contract.new_intent(
+            "intent".to_string(),
+            "solver.deposit".parse().unwrap(),
+            "h".repeat(DEFAULT_MAX_HASH_LEN as usize + 1),
+            U128(1_000_000),
+        );
+    }
+
+    #[test]
+    fn new_intent_accepts_data_at_size_limits() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .predecessor("solver.test")
+            .build();
+        contract.new_intent(
+            "x".repeat(DEFAULT_MAX_INTENT_DATA_LEN as usize),
+            "solver.deposit".parse().unwrap(),
+            "h".repeat(DEFAULT_MAX_HASH_LEN as usize),
+            U128(1_000_000),
+        );
+        assert!(contract.borrow_in_flight);
+
+        init_account("owner.test", 0);
+        contract.set_intent_size_limits(16, 8);
+        let config = contract.get_vault_config();
+        assert_eq!(config.max_intent_data_len, 16);
+        assert_eq!(config.max_hash_len, 8);
+    }
+
     #[test]
     fn new_intent_reduces_total_assets_by_requested_amount() {
         let mut contract = ContractBuilder::new("owner.test", "usdc.test")
@@ -400,7 +1827,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Intent with this hash already exists")]
+    #[should_panic(expected = "E_DUPLICATE_HASH: Intent with this hash already exists")]
     fn duplicate_user_deposit_hash_panics() {
         let mut contract = ContractBuilder::new("owner.test", "usdc.test")
             .total_assets(10_000_000)
@@ -412,6 +1839,8 @@ mod tests {
             "intent".to_string(),
             "dup-hash".to_string(),
             U128(5_000_000),
+            false,
+            contract.solver_fee,
         );
         contract.new_intent(
             "intent".to_string(),
@@ -422,7 +1851,37 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "No intents for solver")]
+    fn get_intent_by_hash_finds_matching_intent() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        for (solver, hash) in [("solver.test", "hash-a"), ("solver2.test", "hash-b")] {
+            contract.insert_intent(
+                solver.parse().unwrap(),
+                format!("intent-{}", hash),
+                hash.to_string(),
+                U128(1_000_000),
+                false,
+                contract.solver_fee,
+            );
+        }
+
+        let found = contract.get_intent_by_hash("hash-b".to_string()).unwrap();
+        assert_eq!(found.index, U128(1));
+        assert_eq!(found.intent.user_deposit_hash, "hash-b");
+        assert_eq!(found.intent.intent_data, "intent-hash-b");
+        assert_eq!(
+            contract
+                .get_intent_by_hash("hash-a".to_string())
+                .unwrap()
+                .index,
+            U128(0)
+        );
+        assert!(contract.get_intent_by_hash("unknown".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "E_NO_INTENTS: Solver has no intents")]
     fn update_intent_state_restricted_to_owner_solver() {
         let mut contract = ContractBuilder::new("owner.test", "usdc.test")
             .total_assets(10_000_000)
@@ -434,11 +1893,250 @@ mod tests {
             "intent".to_string(),
             "hash-x".to_string(),
             U128(5_000_000),
+            false,
+            contract.solver_fee,
         );
         init_account("hacker.test", 1);
         contract.update_intent_state(0, State::SwapCompleted);
     }
 
+    fn contract_with_intents(hashes: &[(&str, &str)]) -> Contract {
+        hashes
+            .iter()
+            .fold(
+                ContractBuilder::new("owner.test", "usdc.test").total_assets(10_000_000),
+                |builder, (solver, hash)| builder.intent(solver, hash, 1_000_000),
+            )
+            .build()
+    }
+
+    #[test]
+    fn update_intent_states_applies_whole_batch() {
+        let mut contract = contract_with_intents(&[("solver.test", "h-0"), ("solver.test", "h-1")]);
+
+        init_account("solver.test", 0);
+        contract.update_intent_states(vec![
+            (0, State::StpLiquidityDeposited),
+            (1, State::SwapCompleted),
+        ]);
+
+        assert!(contract.index_to_intent.get(&0).unwrap().state == State::StpLiquidityDeposited);
+        assert!(contract.index_to_intent.get(&1).unwrap().state == State::SwapCompleted);
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(
+            logs[0]
+                .matches("\"old_state\":\"StpLiquidityBorrowed\"")
+                .count(),
+            2
+        );
+        assert!(logs[0].contains("\"intent_state_changed\""));
+    }
+
+    #[test]
+    fn hash_and_index_address_the_same_intent() {
+        let mut contract = contract_with_intents(&[("solver.test", "h-0"), ("solver.test", "h-1")]);
+        assert_eq!(contract.hash_to_index("h-1".to_string()), Some(U128(1)));
+        assert_eq!(contract.hash_to_index("h-9".to_string()), None);
+
+        init_account("solver.test", 0);
+        contract.update_intent_state_by_hash("h-1".to_string(), State::StpLiquidityDeposited);
+        assert!(contract.index_to_intent.get(&1).unwrap().state == State::StpLiquidityDeposited);
+
+        contract.update_intent_state(1, State::SwapCompleted);
+        let by_hash = contract.get_intent_by_hash("h-1".to_string()).unwrap();
+        assert_eq!(by_hash.index, U128(1));
+        assert!(by_hash.intent.state == State::SwapCompleted);
+        // The other intent is untouched
+        assert!(contract.index_to_intent.get(&0).unwrap().state == State::StpLiquidityBorrowed);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_INTENT_NOT_OWNED: Intent not owned by solver")]
+    fn update_intent_state_by_hash_checks_ownership() {
+        let mut contract =
+            contract_with_intents(&[("solver.test", "h-0"), ("solver2.test", "h-1")]);
+        init_account("solver.test", 0);
+        contract.update_intent_state_by_hash("h-1".to_string(), State::StpLiquidityDeposited);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_INTENT_NOT_FOUND: Intent not found")]
+    fn update_intent_state_by_unknown_hash_panics() {
+        let mut contract = contract_with_intents(&[("solver.test", "h-0")]);
+        init_account("solver.test", 0);
+        contract.update_intent_state_by_hash("h-9".to_string(), State::StpLiquidityDeposited);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_INTENT_NOT_OWNED: Intent not owned by solver")]
+    fn update_intent_states_rejects_batch_with_unowned_index() {
+        let mut contract =
+            contract_with_intents(&[("solver.test", "h-0"), ("solver2.test", "h-1")]);
+
+        init_account("solver.test", 0);
+        contract.update_intent_states(vec![
+            (0, State::StpLiquidityDeposited),
+            (1, State::StpLiquidityDeposited),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "E_INTENT_NOT_FOUND: Intent not found")]
+    fn update_intent_states_rejects_missing_index() {
+        let mut contract = contract_with_intents(&[("solver.test", "h-0"), ("solver.test", "h-1")]);
+        // Index stays in the solver's list but the intent itself is gone
+        contract.index_to_intent.remove(&1);
+
+        init_account("solver.test", 0);
+        contract.update_intent_states(vec![
+            (0, State::StpLiquidityDeposited),
+            (1, State::StpLiquidityDeposited),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot clear intents with outstanding borrows")]
+    fn clear_intents_blocked_with_outstanding_borrows() {
+        let mut contract = contract_with_intents(&[("solver.test", "h-0")]);
+
+        init_account("owner.test", 0);
+        contract.pause_all();
+        contract.clear_intents(None);
+    }
+
+    #[test]
+    fn forced_clear_intents_reports_abandoned_principal() {
+        let mut contract = contract_with_intents(&[("solver.test", "h-0"), ("solver.test", "h-1")]);
+
+        init_account("owner.test", 0);
+        contract.clear_intents(Some(true));
+
+        assert!(contract.index_to_intent.is_empty());
+        assert_eq!(contract.total_borrowed, 0);
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].contains("\"intents_cleared\":2"));
+        assert!(logs[0].contains("\"abandoned_principal\":\"2000000\""));
+        assert!(logs[0].contains("\"forced\":true"));
+    }
+
+    #[test]
+    fn repayable_within_returns_intents_covered_by_budget() {
+        let contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .intent("solver.test", "h-0", 1_000_000)
+            .intent("solver.test", "h-1", 2_000_000)
+            .intent("solver.test", "h-2", 3_000_000)
+            .build();
+        let solver: AccountId = "solver.test".parse().unwrap();
+
+        // Minimums at 1% fee: 1_010_000, 2_020_000, 3_030_000
+        let covered = contract.repayable_within(solver.clone(), U128(3_500_000));
+        assert_eq!(covered, vec![U128(0), U128(1)]);
+
+        let covered = contract.repayable_within(solver, U128(1_000_000));
+        assert!(covered.is_empty());
+        assert!(contract
+            .repayable_within("nobody.test".parse().unwrap(), U128(10_000_000))
+            .is_empty());
+    }
+
+    #[test]
+    fn default_impact_reports_share_price_drop() {
+        // 10 assets worth of shares at 1:1 (extra_decimals = 3)
+        let contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .supply(10_000_000_000)
+            .intent("solver.test", "hash-d", 4_000_000)
+            .build();
+
+        // Before: (6_000_000 + 4_000_000 + 40_000) * 1e18 / 1e10
+        let before = contract.internal_convert_to_assets(SHARE_PRICE_SCALE, Rounding::Down);
+        assert_eq!(before, 1_004_000_000_000_000);
+
+        let impact = contract.default_impact(U128(0));
+        assert_eq!(impact.principal_lost, U128(4_000_000));
+        assert_eq!(impact.share_price_after, U128(600_000_000_000_000));
+
+        // State is untouched
+        assert_eq!(contract.total_borrowed, 4_000_000);
+        assert!(contract.index_to_intent.get(&0).is_some());
+    }
+
+    /// Builds the `default_impact` scenario with the intent marked returned
+    /// by its solver but never repaid.
+    fn defaulted_contract() -> Contract {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .supply(10_000_000_000)
+            .intent("solver.test", "hash-d", 4_000_000)
+            .build();
+        init_account("solver.test", 0);
+        contract.update_intent_state(0, State::StpLiquidityReturned);
+        contract
+    }
+
+    #[test]
+    fn write_off_lowers_share_price_to_default_impact() {
+        let mut contract = defaulted_contract();
+        assert_eq!(contract.get_exchange_rate(), U128(1_004_000_000_000_000));
+        let impact = contract.default_impact(U128(0));
+
+        init_account("owner.test", 0);
+        contract.write_off_intent(U128(0), None);
+
+        assert_eq!(contract.get_exchange_rate(), impact.share_price_after);
+        assert_eq!(contract.get_exchange_rate(), U128(600_000_000_000_000));
+        assert_eq!(contract.total_borrowed, 0);
+        assert_eq!(contract.total_assets, 6_000_000);
+        assert_eq!(contract.get_total_written_off(), U128(4_000_000));
+        assert!(contract.index_to_intent.get(&0).is_none());
+        assert!(contract.hash_to_intent_index.get("hash-d").is_none());
+        assert!(contract
+            .solver_id_to_indices
+            .get(&"solver.test".parse::<AccountId>().unwrap())
+            .is_none());
+        assert!(contract.check_invariants().healthy);
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains("\"loss_realized\"")
+                && log.contains("\"principal_lost\":\"4000000\"")
+                && log.contains("\"forced\":false")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Intent is still repayable; pass force to write it off")]
+    fn write_off_of_repayable_intent_requires_force() {
+        let mut contract = contract_with_intents(&[("solver.test", "h-0")]);
+        init_account("owner.test", 0);
+        contract.write_off_intent(U128(0), None);
+    }
+
+    #[test]
+    fn forced_write_off_keeps_other_intents() {
+        let mut contract = contract_with_intents(&[("solver.test", "h-0"), ("solver.test", "h-1")]);
+        init_account("owner.test", 0);
+        contract.write_off_intent(U128(0), Some(true));
+
+        assert_eq!(contract.total_borrowed, 1_000_000);
+        assert_eq!(contract.get_total_written_off(), U128(1_000_000));
+        assert_eq!(
+            contract
+                .solver_id_to_indices
+                .get(&"solver.test".parse::<AccountId>().unwrap()),
+            Some(&vec![1])
+        );
+        assert!(get_logs().iter().any(|log| log.contains("\"forced\":true")));
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_off_requires_owner() {
+        let mut contract = defaulted_contract();
+        contract.write_off_intent(U128(0), None);
+    }
+
     #[test]
     fn update_intent_state_by_solver_succeeds() {
         let mut contract = ContractBuilder::new("owner.test", "usdc.test")
@@ -451,6 +2149,8 @@ mod tests {
             "intent".to_string(),
             "hash-y".to_string(),
             U128(5_000_000),
+            false,
+            contract.solver_fee,
         );
         init_account("solver.test", 1);
         contract.update_intent_state(0, State::SwapCompleted);
@@ -458,4 +2158,687 @@ mod tests {
         assert_eq!(intents.len(), 1);
         assert!(matches!(intents[0].intent.state, State::SwapCompleted));
     }
+
+    fn borrow_and_repay(contract: &mut Contract, solver: &AccountId, hashes: &[&str]) {
+        use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+
+        for hash in hashes {
+            let index = contract.intent_nonce;
+            contract.insert_intent(
+                solver.clone(),
+                "intent".to_string(),
+                hash.to_string(),
+                U128(1_000_000),
+                false,
+                contract.solver_fee,
+            );
+            init_account("usdc.test", 0);
+            let msg =
+                serde_json::json!({ "repay": { "intent_index": index.to_string() } }).to_string();
+            let _ = contract.ft_on_transfer(solver.clone(), U128(1_010_000), msg);
+        }
+    }
+
+    /// Opens an intent borrowing `amount` and repays it with `repayment`.
+    fn repay_intent(contract: &mut Contract, amount: u128, repayment: impl Fn(U128) -> u128) {
+        use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+
+        let index = contract.intent_nonce;
+        contract.insert_intent(
+            "solver.test".parse().unwrap(),
+            "intent".to_string(),
+            format!("hash-{}", index),
+            U128(amount),
+            false,
+            contract.solver_fee,
+        );
+        let minimum = contract.get_minimum_repayment(U128(index));
+        init_account("usdc.test", 0);
+        let msg = serde_json::json!({ "repay": { "intent_index": index.to_string() } }).to_string();
+        let _ = contract.ft_on_transfer(
+            "solver.test".parse().unwrap(),
+            U128(repayment(minimum)),
+            msg,
+        );
+    }
+
+    #[test]
+    fn solver_total_owed_sums_minimum_repayments() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .intent("solver.test", "h-0", 1_000_000)
+            .intent("solver.test", "h-1", 1_234_567)
+            .build();
+        let solver: AccountId = "solver.test".parse().unwrap();
+        assert_eq!(
+            contract.get_solver_total_owed("other.test".parse().unwrap()),
+            U128(0)
+        );
+
+        let expected =
+            contract.get_minimum_repayment(U128(0)).0 + contract.get_minimum_repayment(U128(1)).0;
+        assert_eq!(expected, 1_010_000 + 1_246_912);
+        assert_eq!(
+            contract.get_solver_total_owed(solver.clone()),
+            U128(expected)
+        );
+
+        // Advancing the lifecycle keeps an intent owed; marking it returned does not
+        contract.index_to_intent.get_mut(&0).unwrap().state = State::SwapCompleted;
+        contract.index_to_intent.get_mut(&1).unwrap().state = State::StpLiquidityReturned;
+        assert_eq!(contract.get_solver_total_owed(solver), U128(1_010_000));
+    }
+
+    #[test]
+    fn minimum_repayment_view_is_accepted_for_any_borrow_size() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(100_000_000)
+            .build();
+        // Sizes where the 1% fee rounds down, as well as exact ones
+        for amount in [1_000_000, 1_234_567, 99, 50_000_001] {
+            repay_intent(&mut contract, amount, |minimum| minimum.0);
+        }
+        assert!(contract.index_to_intent.is_empty());
+        assert_eq!(contract.get_completed_intents(None, None).len(), 4);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "E_REPAY_TOO_LOW: Repayment 1246911 is less than minimum required 1246912"
+    )]
+    fn one_below_minimum_repayment_view_is_rejected() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(100_000_000)
+            .build();
+        repay_intent(&mut contract, 1_234_567, |minimum| minimum.0 - 1);
+    }
+
+    #[test]
+    fn repaid_intents_are_kept_in_history() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        let solver: AccountId = "solver.test".parse().unwrap();
+        borrow_and_repay(&mut contract, &solver, &["h-0", "h-1"]);
+
+        let history = contract.get_completed_intents(None, None);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].index, U128(0));
+        assert_eq!(history[0].solver_id, solver);
+        assert!(history[0].intent.state == State::StpLiquidityReturned);
+        assert_eq!(history[0].intent.repayment_amount, Some(U128(1_010_000)));
+        assert_eq!(history[1].intent.user_deposit_hash, "h-1");
+        assert!(contract.index_to_intent.is_empty());
+        assert!(contract.get_intent_by_hash("h-0".to_string()).is_none());
+    }
+
+    #[test]
+    fn history_evicts_oldest_at_capacity() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        init_account("owner.test", 0);
+        contract.set_history_capacity(2);
+        let solver: AccountId = "solver.test".parse().unwrap();
+        borrow_and_repay(&mut contract, &solver, &["h-0", "h-1", "h-2", "h-3", "h-4"]);
+
+        let indices: Vec<U128> = contract
+            .get_completed_intents(None, None)
+            .iter()
+            .map(|entry| entry.index)
+            .collect();
+        assert_eq!(indices, vec![U128(3), U128(4)]);
+        let page = contract.get_completed_intents(Some(1), Some(1));
+        assert_eq!(page[0].index, U128(4));
+
+        init_account("owner.test", 0);
+        contract.set_history_capacity(1);
+        let history = contract.get_completed_intents(None, None);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].index, U128(4));
+    }
+
+    fn borrow(contract: &mut Contract, hash: &str) {
+        init_account("solver.test", 0);
+        contract.new_intent(
+            "intent".to_string(),
+            "solver.deposit".parse().unwrap(),
+            hash.to_string(),
+            U128(1_000_000),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "E_BORROW_IN_FLIGHT: Another borrow is in flight")]
+    fn concurrent_borrow_is_rejected() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        borrow(&mut contract, "h-0");
+        assert!(contract.borrow_in_flight);
+        borrow(&mut contract, "h-1");
+    }
+
+    #[test]
+    fn callback_releases_borrow_guard() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        let solver: AccountId = "solver.test".parse().unwrap();
+
+        borrow(&mut contract, "h-0");
+        init_callback_ctx(PromiseResult::Failed);
+        assert!(!contract.on_new_intent_callback(
+            "intent".to_string(),
+            solver.clone(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        ));
+        assert!(!contract.borrow_in_flight);
+        assert_eq!(contract.pending_borrowed, 0);
+        assert_eq!(contract.total_assets, 10_000_000);
+
+        borrow(&mut contract, "h-1");
+        assert_eq!(contract.pending_borrowed, 1_000_000);
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        assert!(contract.on_new_intent_callback(
+            "intent".to_string(),
+            solver,
+            "h-1".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        ));
+        assert!(!contract.borrow_in_flight);
+        assert_eq!(contract.pending_borrowed, 0);
+        assert_eq!(contract.total_borrowed, 1_000_000);
+    }
+
+    /// Returns the `data[0]` of the first logged event named `name`.
+    fn event_data(name: &str) -> Option<serde_json::Value> {
+        get_logs().iter().find_map(|log| {
+            let event: serde_json::Value =
+                serde_json::from_str(log.strip_prefix("EVENT_JSON:")?).ok()?;
+            (event["event"] == name).then(|| event["data"][0].clone())
+        })
+    }
+
+    #[test]
+    fn borrow_pays_deposit_address_and_caller_owns_intent() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        borrow(&mut contract, "h-0");
+
+        let transfer = get_created_receipts()
+            .into_iter()
+            .find(|receipt| receipt.receiver_id.as_str() == "usdc.test")
+            .expect("ft_transfer receipt");
+        match &transfer.actions[0] {
+            near_sdk::mock::MockAction::FunctionCallWeight {
+                method_name, args, ..
+            } => {
+                assert_eq!(method_name, b"ft_transfer");
+                let args: serde_json::Value = serde_json::from_slice(args).unwrap();
+                assert_eq!(args["receiver_id"], "solver.deposit");
+                assert_eq!(args["amount"], "1000000");
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        contract.on_new_intent_callback(
+            "intent".to_string(),
+            "solver.test".parse().unwrap(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        );
+        assert_eq!(
+            contract.get_solver_intent_count("solver.test".parse().unwrap()),
+            1
+        );
+        assert_eq!(
+            contract.get_solver_intent_count("solver.deposit".parse().unwrap()),
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "E_INVALID_DEPOSIT_ADDRESS: usdc.test cannot receive borrowed liquidity"
+    )]
+    fn borrow_rejects_asset_as_deposit_address() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .predecessor("solver.test")
+            .build();
+        contract.new_intent(
+            "intent".to_string(),
+            "usdc.test".parse().unwrap(),
+            "h-0".to_string(),
+            U128(1_000_000),
+        );
+    }
+
+    #[test]
+    fn failed_borrow_is_logged_and_rolled_back() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        borrow(&mut contract, "h-0");
+        assert_eq!(contract.total_assets, 9_000_000);
+
+        init_callback_ctx(PromiseResult::Failed);
+        contract.on_new_intent_callback(
+            "intent".to_string(),
+            "solver.test".parse().unwrap(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        );
+        assert_eq!(contract.total_assets, 10_000_000);
+
+        let failed = contract.get_failed_borrows();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].solver_id.as_str(), "solver.test");
+        assert_eq!(failed[0].amount, U128(1_000_000));
+        assert_eq!(failed[0].user_deposit_hash, "h-0");
+        assert_eq!(failed[0].reason, BORROW_TRANSFER_FAILED);
+    }
+
+    #[test]
+    fn failed_borrow_log_keeps_most_recent_entries() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        for i in 0..MAX_FAILED_BORROWS + 3 {
+            contract.record_failed_borrow(FailedBorrow {
+                solver_id: "solver.test".parse().unwrap(),
+                amount: U128(1),
+                user_deposit_hash: format!("h-{}", i),
+                reason: BORROW_TRANSFER_FAILED.to_string(),
+                failed_at: U64(0),
+            });
+        }
+
+        let failed = contract.get_failed_borrows();
+        assert_eq!(failed.len(), MAX_FAILED_BORROWS as usize);
+        assert_eq!(failed[0].user_deposit_hash, "h-3");
+        assert_eq!(
+            failed.last().unwrap().user_deposit_hash,
+            format!("h-{}", MAX_FAILED_BORROWS + 2)
+        );
+    }
+
+    #[test]
+    fn borrow_callback_emits_created_or_failed_event() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        let solver: AccountId = "solver.test".parse().unwrap();
+
+        borrow(&mut contract, "h-0");
+        init_callback_ctx(PromiseResult::Failed);
+        contract.on_new_intent_callback(
+            "intent".to_string(),
+            solver.clone(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        );
+        let failed = event_data("borrow_failed").expect("borrow_failed event");
+        assert_eq!(failed["solver_id"], "solver.test");
+        assert_eq!(failed["amount"], "1000000");
+        assert_eq!(failed["user_deposit_hash"], "h-0");
+        assert!(event_data("intent_created").is_none());
+
+        borrow(&mut contract, "h-1");
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        contract.on_new_intent_callback(
+            "intent".to_string(),
+            solver,
+            "h-1".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        );
+        let created = event_data("intent_created").expect("intent_created event");
+        assert_eq!(created["solver_id"], "solver.test");
+        assert_eq!(created["intent_index"], "0");
+        assert_eq!(created["borrow_amount"], "1000000");
+        assert_eq!(created["user_deposit_hash"], "h-1");
+        assert_eq!(created["total_borrowed"], "1000000");
+        assert_eq!(created["total_assets"], "9000000");
+        assert!(event_data("borrow_failed").is_none());
+    }
+
+    /// Borrows 1_000_000 from a 10_000_000 vault with a 0.5% origination fee.
+    fn borrow_with_origination_fee() -> Contract {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .supply(10_000_000_000)
+            .build();
+        contract.set_origination_fee_bps(50);
+        borrow(&mut contract, "h-0");
+        contract
+    }
+
+    #[test]
+    fn origination_fee_stays_with_lenders() {
+        let mut contract = borrow_with_origination_fee();
+
+        let transfer = get_created_receipts()
+            .into_iter()
+            .find(|receipt| receipt.receiver_id.as_str() == "usdc.test")
+            .expect("borrow ft_transfer");
+        match &transfer.actions[0] {
+            near_sdk::mock::MockAction::FunctionCallWeight { args, .. } => {
+                let args: serde_json::Value = serde_json::from_slice(args).unwrap();
+                assert_eq!(args["receiver_id"], "solver.deposit");
+                assert_eq!(args["amount"], "995000");
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+        // The 5_000 fee never left the vault
+        assert_eq!(contract.total_assets, 9_005_000);
+        assert_eq!(contract.get_tvl(), U128(10_005_000));
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        contract.on_new_intent_callback(
+            "intent".to_string(),
+            "solver.test".parse().unwrap(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(5_000),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        );
+        assert_eq!(contract.total_borrowed, 1_000_000);
+        assert_eq!(contract.get_tvl(), U128(10_005_000));
+        // The minimum repayment ignores the fee: the full principal plus 1%
+        assert_eq!(contract.get_minimum_repayment(U128(0)), U128(1_010_000));
+        assert_eq!(contract.get_vault_config().origination_fee_bps, 50);
+    }
+
+    #[test]
+    fn failed_borrow_with_origination_fee_restores_assets() {
+        let mut contract = borrow_with_origination_fee();
+
+        init_callback_ctx(PromiseResult::Failed);
+        contract.on_new_intent_callback(
+            "intent".to_string(),
+            "solver.test".parse().unwrap(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(5_000),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        );
+        assert_eq!(contract.total_assets, 10_000_000);
+        assert_eq!(contract.pending_borrowed, 0);
+        assert_eq!(contract.get_tvl(), U128(10_000_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Origination fee cannot exceed 1000 bps")]
+    fn origination_fee_is_capped() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        contract.set_origination_fee_bps(MAX_ORIGINATION_FEE_BPS + 1);
+    }
+
+    #[test]
+    fn borrow_moves_assets_without_changing_tvl() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .supply(10_000_000_000)
+            .build();
+        assert_eq!(contract.get_tvl(), U128(10_000_000));
+        assert_eq!(contract.get_tvl_with_expected_yield(), U128(10_000_000));
+
+        borrow(&mut contract, "h-0");
+        assert_eq!(contract.total_assets, 9_000_000);
+        assert_eq!(contract.get_tvl(), U128(10_000_000));
+        // 1% solver fee on the 1_000_000 in flight
+        assert_eq!(contract.get_tvl_with_expected_yield(), U128(10_010_000));
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        contract.on_new_intent_callback(
+            "intent".to_string(),
+            "solver.test".parse().unwrap(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        );
+        assert_eq!(contract.total_borrowed, 1_000_000);
+        assert_eq!(contract.get_tvl(), U128(10_000_000));
+    }
+
+    #[test]
+    fn in_flight_borrow_keeps_share_price() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .supply(10_000_000_000)
+            .build();
+        let before = contract.internal_convert_to_shares_deposit(1_000_000);
+
+        borrow(&mut contract, "h-0");
+        // Borrowed principal is still vault value, plus its expected yield
+        let in_flight = contract.internal_convert_to_shares_deposit(1_000_000);
+        assert!(in_flight <= before);
+        assert_eq!(in_flight, 999_000_999);
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        contract.on_new_intent_callback(
+            "intent".to_string(),
+            "solver.test".parse().unwrap(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            false,
+        );
+        assert_eq!(
+            contract.internal_convert_to_shares_deposit(1_000_000),
+            in_flight
+        );
+    }
+
+    #[test]
+    fn get_intents_by_state_filters_and_paginates() {
+        let mut contract = (0..5)
+            .fold(
+                ContractBuilder::new("owner.test", "usdc.test").total_assets(10_000_000),
+                |builder, i| builder.intent("solver.test", &format!("h-{}", i), 1_000),
+            )
+            .build();
+        for index in [1u128, 3] {
+            contract.index_to_intent.get_mut(&index).unwrap().state = State::SwapCompleted;
+        }
+
+        let indices = |intents: Vec<IndexedIntent>| -> Vec<u128> {
+            intents.into_iter().map(|i| i.index.0).collect()
+        };
+        assert_eq!(
+            indices(contract.get_intents_by_state(State::StpLiquidityBorrowed, None, None)),
+            vec![0, 2, 4]
+        );
+        assert_eq!(
+            indices(contract.get_intents_by_state(State::SwapCompleted, None, None)),
+            vec![1, 3]
+        );
+        assert_eq!(
+            indices(contract.get_intents_by_state(State::StpLiquidityBorrowed, Some(1), Some(1))),
+            vec![2]
+        );
+        assert_eq!(
+            indices(contract.get_intents_by_state(State::SwapCompleted, Some(2), None)),
+            Vec::<u128>::new()
+        );
+        assert!(contract
+            .get_intents_by_state(State::StpLiquidityReturned, None, None)
+            .is_empty());
+    }
+
+    /// Blocks `solver.test` on a funded vault where it has one open intent.
+    fn contract_with_blocked_solver() -> Contract {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .intent("solver.test", "h-open", 1_000_000)
+            .build();
+        contract.block_solver("solver.test".parse().unwrap());
+        assert!(contract.is_solver_blocked("solver.test".parse().unwrap()));
+        assert!(!contract.is_solver_blocked("other.test".parse().unwrap()));
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "E_SOLVER_BLOCKED: solver.test is blocked from borrowing")]
+    fn blocked_solver_cannot_borrow() {
+        let mut contract = contract_with_blocked_solver();
+        borrow(&mut contract, "h-0");
+    }
+
+    #[test]
+    fn blocked_solver_can_still_repay() {
+        use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+
+        let mut contract = contract_with_blocked_solver();
+        init_account("usdc.test", 0);
+        let msg = serde_json::json!({ "repay": { "intent_index": "0" } }).to_string();
+        let _ = contract.ft_on_transfer("solver.test".parse().unwrap(), U128(1_010_000), msg);
+        assert!(contract.index_to_intent.is_empty());
+        assert_eq!(contract.total_borrowed, 0);
+    }
+
+    #[test]
+    fn unblocked_solver_can_borrow_again() {
+        let mut contract = contract_with_blocked_solver();
+        contract.unblock_solver("solver.test".parse().unwrap());
+        borrow(&mut contract, "h-0");
+        assert!(contract.borrow_in_flight);
+    }
+
+    #[test]
+    #[should_panic]
+    fn block_solver_requires_owner() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .predecessor("alice.test")
+            .build();
+        contract.block_solver("solver.test".parse().unwrap());
+    }
+
+    // ========================================================================
+    // Committed Intent Data
+    // ========================================================================
+
+    const PAYLOAD: &str = r#"{"quote":"q-1","destination":"0xabc"}"#;
+
+    /// Opens a committed intent for `PAYLOAD` and runs its callback.
+    fn committed_intent() -> Contract {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        let commitment = hex::encode(env::sha256_array(PAYLOAD.as_bytes()));
+        init_account("solver.test", 0);
+        contract.new_intent_committed(
+            commitment.to_uppercase(),
+            "solver.deposit".parse().unwrap(),
+            "h-c".to_string(),
+            U128(1_000_000),
+        );
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        assert!(contract.on_new_intent_callback(
+            commitment,
+            "solver.test".parse().unwrap(),
+            "h-c".to_string(),
+            U128(1_000_000),
+            BorrowFees {
+                origination_fee: U128(0),
+                solver_fee: contract.solver_fee,
+            },
+            true,
+        ));
+        contract
+    }
+
+    #[test]
+    fn committed_intent_verifies_only_its_payload() {
+        let contract = committed_intent();
+        let intent = contract.get_intent_by_hash("h-c".to_string()).unwrap();
+        assert!(intent.intent.data_committed);
+        assert_eq!(intent.intent.intent_data.len(), 64);
+
+        assert!(contract.verify_intent_data(intent.index, PAYLOAD.to_string()));
+        assert!(!contract.verify_intent_data(
+            intent.index,
+            r#"{"quote":"q-2","destination":"0xabc"}"#.to_string()
+        ));
+    }
+
+    #[test]
+    fn inline_intent_verifies_by_comparison() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        contract.insert_intent(
+            "solver.test".parse().unwrap(),
+            PAYLOAD.to_string(),
+            "h-0".to_string(),
+            U128(1_000_000),
+            false,
+            contract.solver_fee,
+        );
+        assert!(contract.verify_intent_data(U128(0), PAYLOAD.to_string()));
+        assert!(!contract
+            .verify_intent_data(U128(0), hex::encode(env::sha256_array(PAYLOAD.as_bytes()))));
+    }
+
+    #[test]
+    #[should_panic(expected = "E_INVALID_DATA_COMMITMENT")]
+    fn committed_intent_rejects_malformed_hash() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .predecessor("solver.test")
+            .build();
+        contract.new_intent_committed(
+            "not-a-hash".to_string(),
+            "solver.deposit".parse().unwrap(),
+            "h-c".to_string(),
+            U128(1_000_000),
+        );
+    }
 }
@@ -0,0 +1,186 @@
+//! # Invariants Module
+//!
+//! A single health probe for monitoring and tests. `check_invariants`
+//! recomputes the vault's accounting from storage and reports whether it
+//! still agrees with the tracked totals.
+//!
+//! ## Checks
+//!
+//! | Check                 | Holds when                                                  |
+//! | --------------------- | ----------------------------------------------------------- |
+//! | `shares_backed`       | Value of all shares <= effective assets (NEP-621)           |
+//! | `borrowed_consistent` | `total_borrowed` == sum of open intent borrow amounts       |
+//! | `queue_consistent`    | Queue head <= queue length                                  |
+//!
+//! The intent sum walks every open intent, so the view's gas grows with the
+//! number of outstanding borrows.
+//!
+//! Unit tests additionally run `assert_accounting_consistent` after every
+//! borrow and repayment, so accounting drift fails the test that caused it.
+
+use crate::vault_standards::mul_div::Rounding;
+use crate::*;
+use near_contract_standards::fungible_token::core::FungibleTokenCore;
+
+/// Result of `check_invariants`: each check with the figures it compared.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvariantReport {
+    /// Whether every check passed.
+    pub healthy: bool,
+    /// Whether the value of all shares is covered by effective assets.
+    pub shares_backed: bool,
+    /// Asset value of the full share supply, rounded down.
+    pub shares_value: U128,
+    /// Idle assets plus borrowed principal plus expected yield.
+    pub effective_assets: U128,
+    /// Whether `total_borrowed` matches the open intents.
+    pub borrowed_consistent: bool,
+    /// The tracked `total_borrowed`.
+    pub total_borrowed: U128,
+    /// Sum of borrow amounts over all open intents.
+    pub intents_borrowed: U128,
+    /// Whether the queue head lies within the queue.
+    pub queue_consistent: bool,
+    /// Index of the next queued redemption.
+    pub queue_head: u32,
+    /// Length of the queue's backing vector.
+    pub queue_len: u32,
+}
+
+#[near]
+impl Contract {
+    /// Recomputes the vault's core accounting invariants.
+    ///
+    /// # Returns
+    ///
+    /// An [`InvariantReport`] with each check and the figures behind it.
+    pub fn check_invariants(&self) -> InvariantReport {
+        let total_supply = self.token.ft_total_supply().0;
+        let shares_value = self.internal_convert_to_assets(total_supply, Rounding::Down);
+        let (total_borrowed, expected_yield) = self.calculate_expected_yield();
+        let effective_assets = self
+            .total_assets
+            .saturating_add(total_borrowed)
+            .saturating_add(expected_yield);
+
+        let intents_borrowed = self.index_to_intent.values().fold(0u128, |sum, intent| {
+            sum.saturating_add(intent.borrow_amount.0)
+        });
+
+        let queue_head = self.pending_redemptions_head;
+        let queue_len = self.pending_redemptions.len();
+
+        let shares_backed = shares_value <= effective_assets;
+        let borrowed_consistent = self.total_borrowed == intents_borrowed;
+        let queue_consistent = queue_head <= queue_len;
+
+        InvariantReport {
+            healthy: shares_backed && borrowed_consistent && queue_consistent,
+            shares_backed,
+            shares_value: U128(shares_value),
+            effective_assets: U128(effective_assets),
+            borrowed_consistent,
+            total_borrowed: U128(self.total_borrowed),
+            intents_borrowed: U128(intents_borrowed),
+            queue_consistent,
+            queue_head,
+            queue_len,
+        }
+    }
+}
+
+// ============================================================================
+// Accounting Audit
+// ============================================================================
+
+#[cfg(test)]
+impl Contract {
+    /// Panics if `total_borrowed` or `open_intent_fees` differs from the sum
+    /// over open intents.
+    ///
+    /// Called at the end of the borrow callback and `handle_repayment` in unit
+    /// tests. The sum is overflow-checked, unlike the saturating view above.
+    ///
+    /// # Panics
+    ///
+    /// - If the sum of open intent borrows or fees overflows
+    /// - If either sum differs from its tracked total
+    pub(crate) fn assert_accounting_consistent(&self) {
+        let intents_borrowed = self
+            .index_to_intent
+            .values()
+            .try_fold(0u128, |sum, intent| sum.checked_add(intent.borrow_amount.0))
+            .expect("Accounting audit: open intent borrows overflow");
+        assert_eq!(
+            self.total_borrowed, intents_borrowed,
+            "Accounting audit: total_borrowed does not match open intents"
+        );
+        let intents_fees = self
+            .index_to_intent
+            .values()
+            .map(|intent| Self::internal_solver_fee(intent.borrow_amount.0, intent.solver_fee))
+            .try_fold(0u128, |sum, fee| sum.checked_add(fee))
+            .expect("Accounting audit: open intent fees overflow");
+        assert_eq!(
+            self.open_intent_fees, intents_fees,
+            "Accounting audit: open_intent_fees does not match open intents"
+        );
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::builders::ContractBuilder;
+
+    fn borrowed_contract() -> Contract {
+        ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .supply(10_000_000_000)
+            .intent("solver.test", "hash-1", 4_000_000)
+            .build()
+    }
+
+    #[test]
+    fn consistent_state_is_healthy() {
+        let report = borrowed_contract().check_invariants();
+        assert!(report.healthy);
+        assert_eq!(report.total_borrowed, U128(4_000_000));
+        assert_eq!(report.intents_borrowed, U128(4_000_000));
+        // 6_000_000 idle + 4_000_000 borrowed + 40_000 expected yield
+        assert_eq!(report.effective_assets, U128(10_040_000));
+        assert_eq!(report.shares_value, report.effective_assets);
+    }
+
+    #[test]
+    fn corrupted_total_borrowed_is_flagged() {
+        let mut contract = borrowed_contract();
+        contract.total_borrowed = 1_000_000;
+
+        let report = contract.check_invariants();
+        assert!(!report.healthy);
+        assert!(!report.borrowed_consistent);
+        assert!(report.shares_backed);
+        assert!(report.queue_consistent);
+        assert_eq!(report.total_borrowed, U128(1_000_000));
+        assert_eq!(report.intents_borrowed, U128(4_000_000));
+    }
+
+    #[test]
+    fn audit_passes_for_consistent_state() {
+        borrowed_contract().assert_accounting_consistent();
+    }
+
+    #[test]
+    #[should_panic(expected = "Accounting audit: total_borrowed does not match open intents")]
+    fn audit_panics_on_accounting_drift() {
+        let mut contract = borrowed_contract();
+        contract.total_borrowed += 1;
+        contract.assert_accounting_consistent();
+    }
+}
@@ -0,0 +1,302 @@
+//! # Keeper Rewards Module
+//!
+//! Pays whoever calls `process_next_redemption` a small cut of each payout it
+//! triggers, so queued lenders are not left waiting for a volunteer.
+//!
+//! The reward is `assets_paid * keeper_reward_bps / 10_000` and is funded
+//! from realized yield: each repayment credits its lender yield to
+//! `keeper_reward_budget`, and a reward is only paid while that budget covers
+//! it. On top of that, the reward must fit in the idle surplus left after
+//! every redemption still waiting in the queue is covered. It is never paid
+//! out of lender principal, and never for payouts made by auto-processing
+//! inside a repayment.
+
+use crate::fees::BPS_DENOMINATOR;
+use crate::vault_standards::mul_div::{mul_div, Rounding};
+use crate::*;
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_sdk::{ext_contract, PromiseResult};
+
+/// Upper bound for `keeper_reward_bps` (1% of each payout).
+pub const MAX_KEEPER_REWARD_BPS: u16 = 100;
+
+/// Callback interface for keeper reward transfers.
+#[allow(dead_code)]
+#[ext_contract(ext_self)]
+trait ExtContract {
+    fn on_keeper_reward_paid(&mut self, keeper_id: AccountId, amount: U128) -> U128;
+}
+
+impl Contract {
+    /// Returns the assets still owed to unprocessed queue entries.
+    ///
+    /// Walks the queue from its head, so the cost grows with queue length.
+    pub(crate) fn internal_queued_obligations(&self) -> u128 {
+        (self.pending_redemptions_head..self.pending_redemptions.len())
+            .filter_map(|index| self.pending_redemptions.get(index))
+            .fold(0u128, |sum, entry| sum.saturating_add(entry.assets))
+    }
+
+    /// Returns the reward owed for a payout of `assets_paid`, or 0 if either
+    /// the keeper reward budget or the idle surplus beyond queued obligations
+    /// cannot cover it.
+    pub(crate) fn internal_keeper_reward(&self, assets_paid: u128) -> u128 {
+        let reward = mul_div(
+            assets_paid,
+            self.keeper_reward_bps as u128,
+            BPS_DENOMINATOR as u128,
+            Rounding::Down,
+        );
+        let surplus = self
+            .total_assets
+            .saturating_sub(self.internal_queued_obligations());
+        if reward <= self.keeper_reward_budget && reward <= surplus {
+            reward
+        } else {
+            0
+        }
+    }
+
+    /// Transfers the keeper reward for a payout of `assets_paid`.
+    ///
+    /// The reward leaves `total_assets` and `keeper_reward_budget` up front
+    /// and both are restored by `on_keeper_reward_paid` if the transfer fails.
+    pub(crate) fn internal_pay_keeper_reward(&mut self, keeper_id: AccountId, assets_paid: u128) {
+        let reward = self.internal_keeper_reward(assets_paid);
+        if reward == 0 {
+            return;
+        }
+        self.total_assets -= reward;
+        self.keeper_reward_budget -= reward;
+
+        let _ = ext_ft_core::ext(self.asset.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(self.gas_config.withdraw_transfer)
+            .ft_transfer(
+                keeper_id.clone(),
+                U128(reward),
+                Some("Keeper reward".to_string()),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(self.gas_config.withdraw_callback)
+                    .on_keeper_reward_paid(keeper_id, U128(reward)),
+            );
+    }
+}
+
+#[near]
+impl Contract {
+    /// Sets the reward paid to `process_next_redemption` callers.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper_reward_bps` - Reward in basis points of each payout (0 disables)
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `keeper_reward_bps` exceeds `MAX_KEEPER_REWARD_BPS`
+    pub fn set_keeper_reward_bps(&mut self, keeper_reward_bps: u16) {
+        self.require_owner();
+        require!(
+            keeper_reward_bps <= MAX_KEEPER_REWARD_BPS,
+            format!("Keeper reward cannot exceed {} bps", MAX_KEEPER_REWARD_BPS)
+        );
+        self.keeper_reward_bps = keeper_reward_bps;
+    }
+
+    /// Returns the configured keeper reward in basis points.
+    pub fn get_keeper_reward_bps(&self) -> u16 {
+        self.keeper_reward_bps
+    }
+
+    /// Returns the realized yield still available for keeper rewards.
+    pub fn get_keeper_reward_budget(&self) -> U128 {
+        U128(self.keeper_reward_budget)
+    }
+
+    /// Returns the total keeper rewards paid out so far.
+    pub fn get_total_keeper_rewards(&self) -> U128 {
+        U128(self.total_keeper_rewards)
+    }
+
+    /// Callback after transferring a keeper reward.
+    ///
+    /// Adds the reward to `total_keeper_rewards` on success and returns it to
+    /// `total_assets` and `keeper_reward_budget` if the transfer failed.
+    #[private]
+    pub fn on_keeper_reward_paid(&mut self, keeper_id: AccountId, amount: U128) -> U128 {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                self.total_keeper_rewards = self.total_keeper_rewards.saturating_add(amount.0);
+                amount
+            }
+            _ => {
+                self.log_debug(&format!(
+                    "on_keeper_reward_paid: transfer to {} failed, restoring {}",
+                    keeper_id, amount.0
+                ));
+                self.total_assets = self
+                    .total_assets
+                    .checked_add(amount.0)
+                    .expect("total_assets overflow");
+                self.keeper_reward_budget = self
+                    .keeper_reward_budget
+                    .checked_add(amount.0)
+                    .expect("keeper_reward_budget overflow");
+                U128(0)
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::builders::ContractBuilder;
+    use crate::test_utils::helpers::{init_callback_ctx, init_ctx};
+    use near_sdk::test_utils::get_created_receipts;
+
+    /// Builds a vault holding `total_assets` with one queued redemption of
+    /// 1_000_000 assets for alice, a 50 bps keeper reward and 10_000 of
+    /// realized yield in the reward budget.
+    fn queued_vault(total_assets: u128) -> Contract {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(total_assets)
+            .build();
+        contract.set_keeper_reward_bps(50);
+        contract.keeper_reward_budget = 10_000;
+
+        let lender: AccountId = "alice.test".parse().unwrap();
+        contract.token.internal_register_account(&lender);
+        contract.token.internal_deposit(&lender, 1_000_000_000);
+        contract.pending_redemptions.push(crate::PendingRedemption {
+            owner_id: lender.clone(),
+            receiver_id: lender,
+            shares: 1_000_000_000,
+            assets: 1_000_000,
+            memo: None,
+            storage_deposit: 0,
+            min_assets_out: None,
+        });
+        contract
+    }
+
+    /// Returns the keeper reward amounts transferred to `keeper`.
+    fn reward_transfers(keeper: &str) -> Vec<String> {
+        get_created_receipts()
+            .into_iter()
+            .filter(|receipt| receipt.receiver_id.as_str() == "usdc.test")
+            .filter_map(|receipt| match &receipt.actions[0] {
+                near_sdk::mock::MockAction::FunctionCallWeight { args, .. } => {
+                    let args: serde_json::Value = serde_json::from_slice(args).ok()?;
+                    (args["receiver_id"] == keeper).then(|| args["amount"].to_string())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeper_is_paid_from_surplus() {
+        let mut contract = queued_vault(2_000_000);
+        init_ctx("keeper.test", 0);
+        assert!(contract.process_next_redemption());
+
+        // 50 bps of the 1_000_000 payout
+        assert_eq!(reward_transfers("keeper.test"), vec!["\"5000\""]);
+        assert_eq!(contract.total_assets, 995_000);
+        assert_eq!(contract.get_keeper_reward_budget(), U128(5_000));
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        contract.on_keeper_reward_paid("keeper.test".parse().unwrap(), U128(5_000));
+        assert_eq!(contract.get_total_keeper_rewards(), U128(5_000));
+    }
+
+    #[test]
+    fn no_reward_without_surplus() {
+        // Idle assets exactly cover the queued redemption
+        let mut contract = queued_vault(1_000_000);
+        init_ctx("keeper.test", 0);
+        assert!(contract.process_next_redemption());
+
+        assert!(reward_transfers("keeper.test").is_empty());
+        assert_eq!(contract.total_assets, 0);
+        assert_eq!(contract.get_total_keeper_rewards(), U128(0));
+    }
+
+    #[test]
+    fn no_reward_without_realized_yield() {
+        // Plenty of idle surplus, but no repayment has funded the budget
+        let mut contract = queued_vault(2_000_000);
+        contract.keeper_reward_budget = 0;
+        init_ctx("keeper.test", 0);
+        assert!(contract.process_next_redemption());
+
+        assert!(reward_transfers("keeper.test").is_empty());
+        assert_eq!(contract.total_assets, 1_000_000);
+        assert_eq!(contract.get_total_keeper_rewards(), U128(0));
+    }
+
+    #[test]
+    fn repayment_yield_funds_the_budget() {
+        use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(1_000_000)
+            .intent("solver.test", "h-0", 1_000_000)
+            .build();
+        init_ctx("usdc.test", 0);
+        let msg = serde_json::json!({ "repay": { "intent_index": "0" } }).to_string();
+        let _ = contract.ft_on_transfer("solver.test".parse().unwrap(), U128(1_010_000), msg);
+        assert_eq!(contract.get_keeper_reward_budget(), U128(10_000));
+    }
+
+    #[test]
+    fn reward_never_cuts_into_remaining_queue() {
+        let mut contract = queued_vault(2_002_000);
+        let bob: AccountId = "bob.test".parse().unwrap();
+        contract.pending_redemptions.push(crate::PendingRedemption {
+            owner_id: bob.clone(),
+            receiver_id: bob,
+            shares: 1,
+            assets: 1_000_000,
+            memo: None,
+            storage_deposit: 0,
+            min_assets_out: None,
+        });
+
+        // After alice's payout 1_002_000 is idle and bob is owed 1_000_000,
+        // so the 5_000 reward does not fit in the 2_000 surplus
+        init_ctx("keeper.test", 0);
+        assert!(contract.process_next_redemption());
+        assert!(reward_transfers("keeper.test").is_empty());
+        assert_eq!(contract.total_assets, 1_002_000);
+    }
+
+    #[test]
+    fn failed_reward_transfer_restores_assets() {
+        let mut contract = queued_vault(2_000_000);
+        init_ctx("keeper.test", 0);
+        contract.process_next_redemption();
+
+        init_callback_ctx(PromiseResult::Failed);
+        let paid = contract.on_keeper_reward_paid("keeper.test".parse().unwrap(), U128(5_000));
+        assert_eq!(paid, U128(0));
+        assert_eq!(contract.total_assets, 1_000_000);
+        assert_eq!(contract.get_keeper_reward_budget(), U128(10_000));
+        assert_eq!(contract.get_total_keeper_rewards(), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Keeper reward cannot exceed 100 bps")]
+    fn keeper_reward_is_capped() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        contract.set_keeper_reward_bps(MAX_KEEPER_REWARD_BPS + 1);
+    }
+}
@@ -15,14 +15,25 @@
 //! - [`vault`]: Core vault logic for deposits, redemptions, and share calculations
 //! - [`intents`]: Intent lifecycle management for solver borrowing
 //! - [`withdraw`]: Cross-chain withdrawal functionality (EVM/Solana)
+//! - [`collateral`]: Solver collateral locking of vault shares
+//! - [`pause`]: Granular pause flags for incident response
+//! - [`errors`]: Stable error codes for vault and intent panics
+//! - [`fees`]: Protocol fee split of repayment yield
+//! - [`keeper_rewards`]: Rewards for processing the redemption queue
+//! - [`ownership`]: Two-step owner transfer
+//! - [`recovery`]: Owner recovery of stray non-asset tokens
+//! - [`asset_metadata`]: Asset decimals read from the token's `ft_metadata`
+//! - [`agents`]: Worker agent registration deposits and codehash limits
 //! - [`chainsig`]: MPC signature request handling
 //! - [`near_intents`]: NEAR Intents protocol integration
 //! - [`vault_standards`]: NEP-621 vault standard implementation
 
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    env, near, require,
-    store::{IterableMap, IterableSet, Vector},
+    env,
+    json_types::{U128, U64},
+    near, require,
+    store::{IterableMap, IterableSet, LookupMap, Vector},
     AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise,
 };
 
@@ -30,9 +41,20 @@ use near_contract_standards::fungible_token::{
     core_impl::FungibleToken, metadata::FungibleTokenMetadata,
 };
 
+mod agents;
+mod asset_metadata;
 mod chainsig;
+mod collateral;
+mod errors;
+mod fees;
+mod gas;
 mod intents;
+mod invariants;
+mod keeper_rewards;
 mod near_intents;
+mod ownership;
+mod pause;
+mod recovery;
 mod upgrade;
 mod vault;
 mod vault_standards;
@@ -41,8 +63,20 @@ mod withdraw;
 #[cfg(test)]
 pub mod test_utils;
 
-use intents::Intent;
-use vault::PendingRedemption;
+use errors::VaultError;
+use gas::GasConfig;
+use intents::{
+    CompletedIntent, FailedBorrow, Intent, DEFAULT_HISTORY_CAPACITY,
+    DEFAULT_INTENT_CANCEL_WINDOW_NS, DEFAULT_MAX_HASH_LEN, DEFAULT_MAX_INTENT_DATA_LEN,
+};
+use pause::{PauseFlag, PauseFlags};
+use vault::{
+    LossPayout, PendingRedemption, ShareAllowance, DEFAULT_MAX_MEMO_LEN,
+    DEFAULT_QUEUE_COMPACTION_BATCH, MAX_EXTRA_DECIMALS,
+};
+use vault_standards::events::{EventStandard, DEFAULT_EVENT_STANDARD, DEFAULT_EVENT_VERSION};
+use vault_standards::mul_div::Rounding;
+use withdraw::ChainConfig;
 
 /// Represents a registered TEE worker agent with its attestation codehash.
 #[near(serializers = [json, borsh])]
@@ -50,6 +84,12 @@ use vault::PendingRedemption;
 pub struct Worker {
     /// The codehash from the TEE attestation, used to verify the agent's integrity.
     codehash: String,
+    /// Registration deposit held for the agent, refunded on deregistration.
+    deposit: NearToken,
+    /// Codehash changes made since `window_start`.
+    codehash_changes: u32,
+    /// Start of the current codehash change window (ns).
+    window_start: U64,
 }
 
 /// Storage keys for NEAR SDK collections.
@@ -69,6 +109,32 @@ pub enum StorageKey {
     FungibleToken,
     /// Storage prefix for the pending redemption queue.
     PendingRedemptions,
+    /// Storage prefix for shares locked as solver collateral.
+    LockedCollateral,
+    /// Storage prefix for public keys registered with NEAR Intents.
+    RegisteredPublicKeys,
+    /// Storage prefix for the redemption receiver allowlist.
+    AllowedReceivers,
+    /// Storage prefix for guardian accounts.
+    Guardians,
+    /// Storage prefix for keeper accounts.
+    Keepers,
+    /// Storage prefix for the completed intent history.
+    CompletedIntents,
+    /// Storage prefix for the intent index by user deposit hash.
+    HashToIntentIndex,
+    /// Storage prefix for the failed borrow log.
+    FailedBorrows,
+    /// Storage prefix for shares escrowed by queued redemptions.
+    QueueEscrow,
+    /// Accounts blocked from receiving share transfers.
+    ShareTransferBlocklist,
+    /// Share redemption allowances by (owner, spender).
+    ShareAllowances,
+    /// Per-chain bridge withdrawal limits.
+    ChainConfig,
+    /// Storage prefix for solvers blocked from borrowing.
+    BlockedSolvers,
 }
 
 /// Main contract state containing vault, intent, and agent management data.
@@ -77,8 +143,8 @@ pub enum StorageKey {
 pub struct Contract {
     /// The account authorized to manage contract settings.
     pub owner_id: AccountId,
-    /// Whether the contract is paused (all state-changing operations blocked).
-    pub is_paused: bool,
+    /// Operation classes currently paused (all set blocks every state-changing operation).
+    pub pause_flags: PauseFlags,
     /// Set of approved TEE codehashes for worker agent verification.
     pub approved_codehashes: IterableSet<String>,
     /// Set of approved solver account IDs.
@@ -111,6 +177,129 @@ pub struct Contract {
     pub pending_redemptions: Vector<PendingRedemption>,
     /// Head index of the pending redemptions queue.
     pub pending_redemptions_head: u32,
+    /// Shares locked as solver collateral, keyed by solver account.
+    pub locked_collateral: IterableMap<AccountId, u128>,
+    /// Public keys successfully registered with the NEAR Intents contract.
+    pub registered_public_keys: IterableSet<String>,
+    /// Whether debug logs are emitted (structured events are always emitted).
+    pub verbose_logging: bool,
+    /// Accounts allowed to receive redeemed assets; `None` allows any receiver.
+    pub allowed_receivers: Option<IterableSet<AccountId>>,
+    /// Account proposed as the next owner, pending its acceptance.
+    pub pending_owner: Option<AccountId>,
+    /// Accounts allowed to toggle pause flags alongside the owner.
+    pub guardians: IterableSet<AccountId>,
+    /// Maximum vault value (available plus borrowed assets) accepted via deposits.
+    pub deposit_cap: Option<u128>,
+    /// Processed queue entries that trigger draining the queue front.
+    pub queue_compaction_batch: u32,
+    /// Cumulative assets donated to the vault without minting shares.
+    pub total_donated: u128,
+    /// Accounts allowed to process the redemption queue when `enforce_keeper` is set.
+    pub keepers: IterableSet<AccountId>,
+    /// Whether queue processing is restricted to keepers and the owner.
+    pub enforce_keeper: bool,
+    /// Ring buffer of the most recently repaid intents, for auditing.
+    pub completed_intents: Vector<CompletedIntent>,
+    /// Slot in `completed_intents` holding the oldest entry once the buffer is full.
+    pub completed_intents_cursor: u32,
+    /// Maximum number of entries kept in `completed_intents`.
+    pub history_capacity: u32,
+    /// Set while a `new_intent` transfer awaits its callback; blocks concurrent borrows.
+    pub borrow_in_flight: bool,
+    /// Decimals of the underlying asset token; share decimals are this plus `extra_decimals`.
+    pub asset_decimals: u8,
+    /// Minimum size of the deposit that seeds an empty vault (no shares outstanding).
+    pub min_first_deposit: u128,
+    /// Index of open intents by user deposit hash.
+    pub hash_to_intent_index: IterableMap<String, u128>,
+    /// Static gas attached to asset transfers and their callbacks.
+    pub gas_config: GasConfig,
+    /// Principal sent by a `new_intent` whose transfer has not yet been confirmed.
+    pub pending_borrowed: u128,
+    /// Blocks after a redemption payout during which borrows are rejected.
+    pub borrow_cooldown_blocks: u64,
+    /// Block height of the most recent redemption payout.
+    pub last_redemption_block: u64,
+    /// Maximum byte length of `intent_data` accepted by `new_intent`.
+    pub max_intent_data_len: u32,
+    /// Maximum byte length of `user_deposit_hash` accepted by `new_intent`.
+    pub max_hash_len: u32,
+    /// Share of repayment yield routed to the treasury, in basis points.
+    pub protocol_fee_bps: u16,
+    /// Account that receives claimed protocol fees.
+    pub treasury_id: Option<AccountId>,
+    /// Protocol fees held by the vault but excluded from `total_assets`.
+    pub accrued_protocol_fees: u128,
+    /// Maximum byte length of a memo on deposits, donations and redemptions.
+    pub max_memo_len: u32,
+    /// Rounding applied to shares minted by non-bootstrap deposits.
+    pub deposit_rounding: Rounding,
+    /// Maximum open intents per solver (`None` = unlimited).
+    pub max_intents_per_solver: Option<u32>,
+    /// Most recent failed borrow attempts, capped at `MAX_FAILED_BORROWS`.
+    pub failed_borrows: Vector<FailedBorrow>,
+    /// Slot in `failed_borrows` holding the oldest entry once the log is full.
+    pub failed_borrows_cursor: u32,
+    /// Reward paid to `process_next_redemption` callers, in basis points of each payout.
+    pub keeper_reward_bps: u16,
+    /// Cumulative keeper rewards paid out.
+    pub total_keeper_rewards: u128,
+    /// Whether queued redemptions move the owner's shares into escrow at queue time.
+    pub burn_on_queue: bool,
+    /// Shares held in escrow for each owner's queued redemption.
+    pub queue_escrow: LookupMap<AccountId, u128>,
+    /// Lender yield realized by repayments within the APY window.
+    pub cumulative_yield: u128,
+    /// Start of the realized-yield window, in nanoseconds (0 until the first deposit).
+    pub yield_window_start: u64,
+    /// Cumulative borrowed principal written off as lost.
+    pub total_written_off: u128,
+    /// Fee kept from each borrow as immediate lender yield, in basis points.
+    pub origination_fee_bps: u16,
+    /// Accounts that may not receive vault shares via `ft_transfer`/`ft_transfer_call`.
+    pub share_transfer_blocklist: IterableSet<AccountId>,
+    /// Idle assets borrows may never draw below.
+    pub min_reserve: u128,
+    /// Shares each owner lets a spender redeem on their behalf, keyed by (owner, spender).
+    pub share_allowances: IterableMap<(AccountId, AccountId), ShareAllowance>,
+    /// `standard` field of emitted vault events.
+    pub event_standard: String,
+    /// `version` field of emitted vault events.
+    pub event_version: String,
+    /// Per-chain bridge withdrawal limits, keyed by `BridgeChain::as_str`.
+    pub chain_config: IterableMap<String, ChainConfig>,
+    /// Per-account share balance limit (`None` = unlimited).
+    pub max_account_shares: Option<u128>,
+    /// Smallest non-zero share balance a transfer may leave its sender (`None` = no dust rule).
+    pub min_share_balance: Option<u128>,
+    /// Solvers barred from opening new intents.
+    pub blocked_solvers: IterableSet<AccountId>,
+    /// How long after borrowing a solver may cancel an intent by returning the principal, in nanoseconds.
+    pub intent_cancel_window_ns: u64,
+    /// Pro-rata payout state kept between `force_process_with_loss` calls.
+    pub loss_payout: Option<LossPayout>,
+    /// Solver fees owed on open intents, each at the fee snapshotted when it was borrowed.
+    pub open_intent_fees: u128,
+    /// Realized lender yield not yet spent on keeper rewards.
+    pub keeper_reward_budget: u128,
+}
+
+impl Contract {
+    /// Returns the standard and version to stamp on emitted events.
+    pub(crate) fn event_standard(&self) -> EventStandard<'_> {
+        EventStandard {
+            name: &self.event_standard,
+            version: &self.event_version,
+        }
+    }
+
+    /// Logs a debug message if verbose logging is enabled.
+    pub(crate) fn log_debug(&self, message: &str) {
+        if self.verbose_logging {
+            env::log_str(message);
+        }
+    }
 }
 
 #[near]
@@ -122,24 +311,57 @@ impl Contract {
     /// * `owner_id` - Account authorized to manage contract settings
     /// * `asset` - Account ID of the underlying NEP-141 asset token
     /// * `metadata` - Fungible token metadata for vault shares
+    /// * `asset_decimals` - Decimals of the underlying asset token, replaced by
+    ///   the asset's own `ft_metadata` decimals if it reports different ones
     /// * `extra_decimals` - Additional decimal precision for shares
     /// * `solver_fee` - Fee percentage solvers must pay on repayment (e.g., 1 = 1%)
     ///
     /// # Returns
     ///
     /// A new `Contract` instance with initialized state.
+    ///
+    /// # Panics
+    ///
+    /// - If `metadata.decimals` is not `asset_decimals + extra_decimals`
+    /// - If `extra_decimals` exceeds `MAX_EXTRA_DECIMALS`
+    /// - If the default minimum first deposit, scaled to shares, overflows `u128`
     #[init]
     #[private]
     pub fn init(
         owner_id: AccountId,
         asset: AccountId,
         metadata: FungibleTokenMetadata,
+        asset_decimals: u8,
         extra_decimals: u8,
         solver_fee: u8,
     ) -> Self {
+        require!(
+            asset_decimals.checked_add(extra_decimals) == Some(metadata.decimals),
+            format!(
+                "Share decimals {} must equal asset_decimals {} + extra_decimals {}",
+                metadata.decimals, asset_decimals, extra_decimals
+            )
+        );
+        require!(
+            extra_decimals <= MAX_EXTRA_DECIMALS,
+            format!(
+                "extra_decimals {} exceeds maximum {}",
+                extra_decimals, MAX_EXTRA_DECIMALS
+            )
+        );
+        // The seed deposit mints min_first_deposit * 10^extra_decimals shares
+        require!(
+            10u128.checked_pow(metadata.decimals as u32).is_some(),
+            format!(
+                "Share decimals {} overflow u128 for the minimum first deposit",
+                metadata.decimals
+            )
+        );
+        // Confirm asset_decimals against the asset once it is reachable
+        let _ = asset_metadata::fetch_asset_decimals(asset.clone());
         Self {
             owner_id,
-            is_paused: false,
+            pause_flags: PauseFlags::NONE,
             approved_codehashes: IterableSet::new(StorageKey::ApprovedCodehashes),
             approved_solvers: IterableSet::new(StorageKey::ApprovedSolvers),
             worker_by_account_id: IterableMap::new(StorageKey::WorkerByAccountId),
@@ -155,6 +377,59 @@ impl Contract {
             solver_fee,
             pending_redemptions: Vector::new(StorageKey::PendingRedemptions),
             pending_redemptions_head: 0,
+            locked_collateral: IterableMap::new(StorageKey::LockedCollateral),
+            registered_public_keys: IterableSet::new(StorageKey::RegisteredPublicKeys),
+            verbose_logging: false,
+            allowed_receivers: None,
+            pending_owner: None,
+            guardians: IterableSet::new(StorageKey::Guardians),
+            deposit_cap: None,
+            queue_compaction_batch: DEFAULT_QUEUE_COMPACTION_BATCH,
+            total_donated: 0,
+            keepers: IterableSet::new(StorageKey::Keepers),
+            enforce_keeper: false,
+            completed_intents: Vector::new(StorageKey::CompletedIntents),
+            completed_intents_cursor: 0,
+            history_capacity: DEFAULT_HISTORY_CAPACITY,
+            borrow_in_flight: false,
+            asset_decimals,
+            min_first_deposit: 10u128.pow(asset_decimals as u32),
+            hash_to_intent_index: IterableMap::new(StorageKey::HashToIntentIndex),
+            gas_config: GasConfig::default(),
+            pending_borrowed: 0,
+            borrow_cooldown_blocks: 0,
+            last_redemption_block: 0,
+            max_intent_data_len: DEFAULT_MAX_INTENT_DATA_LEN,
+            max_hash_len: DEFAULT_MAX_HASH_LEN,
+            protocol_fee_bps: 0,
+            treasury_id: None,
+            accrued_protocol_fees: 0,
+            max_memo_len: DEFAULT_MAX_MEMO_LEN,
+            deposit_rounding: Rounding::Down,
+            max_intents_per_solver: None,
+            failed_borrows: Vector::new(StorageKey::FailedBorrows),
+            failed_borrows_cursor: 0,
+            keeper_reward_bps: 0,
+            total_keeper_rewards: 0,
+            burn_on_queue: false,
+            queue_escrow: LookupMap::new(StorageKey::QueueEscrow),
+            cumulative_yield: 0,
+            yield_window_start: 0,
+            total_written_off: 0,
+            origination_fee_bps: 0,
+            share_transfer_blocklist: IterableSet::new(StorageKey::ShareTransferBlocklist),
+            min_reserve: 0,
+            share_allowances: IterableMap::new(StorageKey::ShareAllowances),
+            event_standard: DEFAULT_EVENT_STANDARD.to_string(),
+            event_version: DEFAULT_EVENT_VERSION.to_string(),
+            chain_config: IterableMap::new(StorageKey::ChainConfig),
+            max_account_shares: None,
+            min_share_balance: None,
+            blocked_solvers: IterableSet::new(StorageKey::BlockedSolvers),
+            intent_cancel_window_ns: DEFAULT_INTENT_CANCEL_WINDOW_NS,
+            loss_payout: None,
+            open_intent_fees: 0,
+            keeper_reward_budget: 0,
         }
     }
 
@@ -167,37 +442,108 @@ impl Contract {
         require!(env::predecessor_account_id() == self.owner_id);
     }
 
-    /// Asserts that the contract is not paused.
+    /// Asserts that the contract is not fully paused.
+    ///
+    /// Operations with their own pause flag are checked via
+    /// `require_not_paused_for` instead.
     ///
     /// # Panics
     ///
-    /// Panics if the contract is currently paused.
+    /// Panics if every pause flag is set.
     pub fn require_not_paused(&self) {
-        require!(!self.is_paused, "Contract is paused");
+        require!(!self.pause_flags.is_all(), "Contract is paused");
     }
 
-    /// Pauses the contract, blocking all state-changing operations.
+    /// Enables or disables debug logging on high-frequency methods.
     ///
-    /// Only the contract owner can pause. View methods remain accessible.
+    /// Structured NEP-297 events are emitted regardless of this setting.
     ///
     /// # Panics
     ///
     /// Panics if caller is not the contract owner.
-    pub fn pause(&mut self) {
+    pub fn set_verbose_logging(&mut self, enabled: bool) {
         self.require_owner();
-        self.is_paused = true;
+        self.verbose_logging = enabled;
     }
 
-    /// Unpauses the contract, resuming normal operations.
+    /// Sets the fee percentage solvers pay on repayment.
     ///
-    /// Only the contract owner can unpause.
+    /// Applies to intents borrowed from now on. Open intents keep the fee
+    /// they were borrowed at, for both their minimum repayment and the
+    /// expected yield lenders are credited with.
+    ///
+    /// # Arguments
+    ///
+    /// * `solver_fee` - Fee percentage (e.g., 1 = 1%)
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `solver_fee` exceeds 100
+    pub fn set_solver_fee(&mut self, solver_fee: u8) {
+        self.require_owner();
+        require!(solver_fee <= 100, "Solver fee cannot exceed 100%");
+        self.solver_fee = solver_fee;
+    }
+
+    /// Sets the `standard` and `version` stamped on emitted vault events.
+    ///
+    /// Lets forks sharing an indexer namespace their events; `init` starts
+    /// with `nep000` / `1.0.0`. NEP-141 share events (`ft_mint`, `ft_burn`,
+    /// `ft_transfer`) keep their own standard.
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `standard` or `version` is empty
+    pub fn set_event_standard(&mut self, standard: String, version: String) {
+        self.require_owner();
+        require!(
+            !standard.is_empty() && !version.is_empty(),
+            "Event standard and version must not be empty"
+        );
+        self.event_standard = standard;
+        self.event_version = version;
+    }
+
+    /// Sets the maximum vault value accepted via deposits.
+    ///
+    /// # Arguments
+    ///
+    /// * `cap` - Cap on `total_assets + total_borrowed + pending_borrowed`,
+    ///   or `None` for no cap
     ///
     /// # Panics
     ///
     /// Panics if caller is not the contract owner.
-    pub fn unpause(&mut self) {
+    pub fn set_deposit_cap(&mut self, cap: Option<U128>) {
+        self.require_owner();
+        self.deposit_cap = cap.map(|cap| cap.0);
+    }
+
+    /// Sets the minimum deposit accepted while no shares are outstanding.
+    ///
+    /// Defaults to one whole asset token. Raising it makes share-price
+    /// inflation through a tiny seed deposit and a donation uneconomical.
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If the shares minted for `amount` as a seed deposit overflow u128
+    pub fn set_min_first_deposit(&mut self, amount: U128) {
         self.require_owner();
-        self.is_paused = false;
+        // The seed deposit mints min_first_deposit * 10^extra_decimals shares
+        require!(
+            amount
+                .0
+                .checked_mul(10u128.pow(self.extra_decimals as u32))
+                .is_some(),
+            format!(
+                "Minimum first deposit {} overflows u128 at {} extra decimals",
+                amount.0, self.extra_decimals
+            )
+        );
+        self.min_first_deposit = amount.0;
     }
 
     /// Approves a TEE codehash for worker agent registration.
@@ -233,6 +579,10 @@ impl Contract {
     /// In production, this should verify the TEE attestation before registration.
     /// Currently simplified for local development.
     ///
+    /// A first registration must attach the refundable deposit returned by
+    /// `get_registration_deposit`; re-registering only updates the
+    /// codehash. See [`agents`] for the codehash change limit.
+    ///
     /// # Arguments
     ///
     /// * `codehash` - The TEE attestation codehash for this agent
@@ -240,11 +590,17 @@ impl Contract {
     /// # Returns
     ///
     /// `true` if registration succeeded.
+    ///
+    /// # Panics
+    ///
+    /// - If `codehash` is longer than 64 bytes
+    /// - If a first registration attaches too small a deposit
+    /// - If the agent changes its codehash too often
+    #[payable]
     pub fn register_agent(&mut self, codehash: String) -> bool {
-        self.require_not_paused();
+        self.require_not_paused_for(PauseFlag::Signatures);
         let predecessor = env::predecessor_account_id();
-        self.worker_by_account_id
-            .insert(predecessor, Worker { codehash });
+        self.internal_register_agent(predecessor, codehash);
 
         true
     }
@@ -259,18 +615,34 @@ impl Contract {
     /// * `path` - BIP-32 derivation path for key generation
     /// * `payload` - The data to sign (hex-encoded hash)
     /// * `key_type` - Either "Ecdsa" for secp256k1 or "Eddsa" for ed25519
+    /// * `intent_index` - Intent to advance to `StpLiquidityDeposited` once
+    ///   the signature succeeds
     ///
     /// # Returns
     ///
-    /// A promise that resolves to the signature.
+    /// A promise that resolves to the signature (`None` if an intent-linked
+    /// request failed).
+    ///
+    /// # Panics
+    ///
+    /// - On an unknown `key_type`, an empty `path`, or a payload that is not
+    ///   a 32-byte hex hash
+    /// - If `intent_index` is set and the caller doesn't own that intent, or
+    ///   it is not `StpLiquidityBorrowed`
     pub fn request_signature(
         &mut self,
         path: String,
         payload: String,
         key_type: String,
+        intent_index: Option<U128>,
     ) -> Promise {
-        self.require_not_paused();
-        chainsig::internal_request_signature(path, payload, key_type)
+        self.require_not_paused_for(PauseFlag::Signatures);
+        match intent_index {
+            Some(intent_index) => {
+                self.internal_request_intent_signature(path, payload, key_type, intent_index.0)
+            }
+            None => chainsig::internal_request_signature(path, payload, key_type),
+        }
     }
 
     /// Adds a public key to the NEAR Intents contract.
@@ -284,9 +656,9 @@ impl Contract {
     ///
     /// # Returns
     ///
-    /// A promise for the cross-contract call result.
+    /// A promise that records the key locally once the call succeeds.
     pub fn add_public_key(&mut self, public_key: String) -> Promise {
-        self.require_not_paused();
+        self.require_not_paused_for(PauseFlag::Signatures);
         near_intents::internal_add_public_key(public_key)
     }
 
@@ -298,9 +670,9 @@ impl Contract {
     ///
     /// # Returns
     ///
-    /// A promise for the cross-contract call result.
+    /// A promise that drops the local record once the call succeeds.
     pub fn remove_public_key(&mut self, public_key: String) -> Promise {
-        self.require_not_paused();
+        self.require_not_paused_for(PauseFlag::Signatures);
         near_intents::internal_remove_public_key(public_key)
     }
 
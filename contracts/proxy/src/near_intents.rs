@@ -17,7 +17,7 @@
 
 use crate::*;
 
-use near_sdk::ext_contract;
+use near_sdk::{ext_contract, PromiseResult};
 
 // ============================================================================
 // Constants
@@ -32,6 +32,9 @@ const GAS: Gas = Gas::from_tgas(10);
 /// Deposit required for Intents contract calls (1 yoctoNEAR).
 const ATTACHED_DEPOSIT: NearToken = NearToken::from_yoctonear(1);
 
+/// Gas allocation for the public key resolution callbacks.
+const GAS_FOR_PUBLIC_KEY_CALLBACK: Gas = Gas::from_tgas(5);
+
 // ============================================================================
 // External Contract Interface
 // ============================================================================
@@ -47,6 +50,14 @@ trait IntentsContract {
     fn remove_public_key(&self, public_key: String) -> Promise;
 }
 
+/// Callback interface for resolving public key registration.
+#[allow(dead_code)]
+#[ext_contract(ext_self)]
+trait ExtContract {
+    fn on_add_public_key(&mut self, public_key: String) -> bool;
+    fn on_remove_public_key(&mut self, public_key: String) -> bool;
+}
+
 // ============================================================================
 // Internal Functions
 // ============================================================================
@@ -62,7 +73,7 @@ trait IntentsContract {
 ///
 /// # Returns
 ///
-/// A promise for the cross-contract call result.
+/// A promise that resolves via `on_add_public_key`.
 pub fn internal_add_public_key(public_key: String) -> Promise {
     // =========================================================================
     // Cross-Contract Call: Add Public Key to Intents
@@ -74,7 +85,12 @@ pub fn internal_add_public_key(public_key: String) -> Promise {
     intents_contract::ext(INTENTS_CONTRACT_ID.parse().unwrap())
         .with_static_gas(GAS)
         .with_attached_deposit(ATTACHED_DEPOSIT)
-        .add_public_key(public_key)
+        .add_public_key(public_key.clone())
+        .then(
+            ext_self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_PUBLIC_KEY_CALLBACK)
+                .on_add_public_key(public_key),
+        )
 }
 
 /// Removes a public key from the NEAR Intents contract.
@@ -88,7 +104,7 @@ pub fn internal_add_public_key(public_key: String) -> Promise {
 ///
 /// # Returns
 ///
-/// A promise for the cross-contract call result.
+/// A promise that resolves via `on_remove_public_key`.
 pub fn internal_remove_public_key(public_key: String) -> Promise {
     // =========================================================================
     // Cross-Contract Call: Remove Public Key from Intents
@@ -99,5 +115,128 @@ pub fn internal_remove_public_key(public_key: String) -> Promise {
     intents_contract::ext(INTENTS_CONTRACT_ID.parse().unwrap())
         .with_static_gas(GAS)
         .with_attached_deposit(ATTACHED_DEPOSIT)
-        .remove_public_key(public_key)
+        .remove_public_key(public_key.clone())
+        .then(
+            ext_self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_PUBLIC_KEY_CALLBACK)
+                .on_remove_public_key(public_key),
+        )
+}
+
+// ============================================================================
+// Contract Implementation
+// ============================================================================
+
+#[near]
+impl Contract {
+    /// Callback after attempting to add a public key to the Intents contract.
+    ///
+    /// Records the key locally only if the cross-contract call succeeded,
+    /// so a failed add never leaves a phantom entry.
+    #[private]
+    pub fn on_add_public_key(&mut self, public_key: String) -> bool {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                self.registered_public_keys.insert(public_key);
+                true
+            }
+            _ => {
+                env::log_str(&format!("add_public_key failed for {}", public_key));
+                false
+            }
+        }
+    }
+
+    /// Callback after attempting to remove a public key from the Intents contract.
+    ///
+    /// Drops the key from the local set only if the removal succeeded.
+    #[private]
+    pub fn on_remove_public_key(&mut self, public_key: String) -> bool {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                self.registered_public_keys.remove(&public_key);
+                true
+            }
+            _ => {
+                env::log_str(&format!("remove_public_key failed for {}", public_key));
+                false
+            }
+        }
+    }
+
+    /// Returns public keys registered with the Intents contract, with optional pagination.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_index` - Starting index for pagination (default: 0)
+    /// * `limit` - Maximum number of keys to return (default: all)
+    pub fn get_registered_public_keys(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<String> {
+        let from = from_index.unwrap_or(0) as usize;
+        let limit = limit.unwrap_or(self.registered_public_keys.len()) as usize;
+
+        self.registered_public_keys
+            .iter()
+            .skip(from)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns whether a public key is registered with the Intents contract.
+    pub fn is_public_key_registered(&self, public_key: String) -> bool {
+        self.registered_public_keys.contains(&public_key)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::{init_callback_ctx, init_contract};
+
+    const KEY: &str = "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp";
+
+    #[test]
+    fn successful_add_and_remove_are_tracked() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        assert!(contract.on_add_public_key(KEY.to_string()));
+        assert!(contract.is_public_key_registered(KEY.to_string()));
+        assert_eq!(
+            contract.get_registered_public_keys(None, None),
+            vec![KEY.to_string()]
+        );
+
+        assert!(contract.on_remove_public_key(KEY.to_string()));
+        assert!(!contract.is_public_key_registered(KEY.to_string()));
+        assert!(contract.get_registered_public_keys(None, None).is_empty());
+    }
+
+    #[test]
+    fn failed_add_leaves_no_phantom_key() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+
+        init_callback_ctx(PromiseResult::Failed);
+        assert!(!contract.on_add_public_key(KEY.to_string()));
+        assert!(!contract.is_public_key_registered(KEY.to_string()));
+    }
+
+    #[test]
+    fn failed_remove_keeps_key() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        contract.on_add_public_key(KEY.to_string());
+        init_callback_ctx(PromiseResult::Failed);
+        assert!(!contract.on_remove_public_key(KEY.to_string()));
+        assert!(contract.is_public_key_registered(KEY.to_string()));
+    }
 }
@@ -0,0 +1,132 @@
+//! # Ownership Transfer Module
+//!
+//! Two-step transfer of the `owner_id` role. The current owner proposes a
+//! successor, and the role only moves once that account accepts. A mistyped
+//! or unreachable account therefore never ends up owning the contract.
+//!
+//! ## Transfer Flow
+//!
+//! 1. Owner calls `propose_owner(new_owner)`
+//! 2. `new_owner` calls `accept_ownership()` and becomes the owner
+//! 3. Until then, the owner can `cancel_ownership_transfer()`
+
+use crate::*;
+
+#[near]
+impl Contract {
+    /// Proposes a new owner, replacing any earlier proposal.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_owner` - The account that may accept ownership
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `new_owner` is already the owner
+    pub fn propose_owner(&mut self, new_owner: AccountId) {
+        self.require_owner();
+        require!(new_owner != self.owner_id, "Account is already the owner");
+
+        env::log_str(&format!(
+            "ownership_proposed owner={} pending_owner={}",
+            self.owner_id, new_owner
+        ));
+        self.pending_owner = Some(new_owner);
+    }
+
+    /// Accepts a pending ownership transfer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the pending owner.
+    pub fn accept_ownership(&mut self) {
+        let caller = env::predecessor_account_id();
+        require!(
+            self.pending_owner.as_ref() == Some(&caller),
+            "Only the pending owner can accept ownership"
+        );
+
+        env::log_str(&format!(
+            "ownership_transferred previous_owner={} owner={}",
+            self.owner_id, caller
+        ));
+        self.owner_id = caller;
+        self.pending_owner = None;
+    }
+
+    /// Cancels a pending ownership transfer.
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If no transfer is pending
+    pub fn cancel_ownership_transfer(&mut self) {
+        self.require_owner();
+        require!(
+            self.pending_owner.take().is_some(),
+            "No ownership transfer pending"
+        );
+    }
+
+    /// Returns the account proposed as the next owner, if any.
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::{init_contract, init_ctx};
+
+    #[test]
+    fn ownership_handshake_transfers_owner() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+        let next: AccountId = "next.test".parse().unwrap();
+
+        init_ctx("owner.test", 0);
+        contract.propose_owner(next.clone());
+        assert_eq!(contract.get_pending_owner(), Some(next.clone()));
+        assert_eq!(contract.owner_id.as_str(), "owner.test");
+
+        init_ctx("next.test", 0);
+        contract.accept_ownership();
+        assert_eq!(contract.owner_id, next);
+        assert!(contract.get_pending_owner().is_none());
+
+        // The new owner can use admin methods
+        contract.set_verbose_logging(true);
+        assert!(contract.verbose_logging);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the pending owner can accept ownership")]
+    fn accept_by_wrong_account_fails() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+
+        init_ctx("owner.test", 0);
+        contract.propose_owner("next.test".parse().unwrap());
+
+        init_ctx("hacker.test", 0);
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the pending owner can accept ownership")]
+    fn cancelled_transfer_cannot_be_accepted() {
+        let mut contract = init_contract("owner.test", "usdc.test");
+
+        init_ctx("owner.test", 0);
+        contract.propose_owner("next.test".parse().unwrap());
+        contract.cancel_ownership_transfer();
+        assert!(contract.get_pending_owner().is_none());
+
+        init_ctx("next.test", 0);
+        contract.accept_ownership();
+    }
+}
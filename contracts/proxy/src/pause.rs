@@ -0,0 +1,463 @@
+//! # Pause Controls Module
+//!
+//! Granular circuit breakers for incident response. Each operation class
+//! can be halted on its own, e.g. stopping new borrows while lenders keep
+//! redeeming and solvers keep repaying.
+//!
+//! ## Flags
+//!
+//! | Flag          | Blocks                                           |
+//! | ------------- | ------------------------------------------------ |
+//! | `deposits`    | Vault and collateral deposits, `storage_deposit` |
+//! | `redemptions` | `redeem`, `withdraw`, `process_next_redemption`, |
+//! |               | share transfers, `storage_withdraw`,             |
+//! |               | `storage_unregister`, `compact_queue`            |
+//! | `borrows`     | `new_intent`, `update_intent_state(s)`,          |
+//! |               | `release_collateral`                             |
+//! | `repayments`  | Repayments via `ft_transfer_call`                |
+//! | `withdrawals` | Cross-chain OMFT withdrawals                     |
+//! | `signatures`  | MPC signature requests, `register_agent`,        |
+//! |               | `add_public_key`, `remove_public_key`            |
+//!
+//! When every flag is set the contract is fully paused and all other
+//! state-changing methods are blocked as well. The owner can still pay out
+//! the redemption queue with `drain_queue_while_paused` to wind down.
+//!
+//! ## Guardians
+//!
+//! Guardians are owner-appointed accounts that can toggle pause flags so
+//! incident response doesn't need the owner key. They cannot change fees,
+//! caps, the asset, or ownership.
+
+use crate::*;
+
+/// An operation class that can be paused independently.
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseFlag {
+    Deposits,
+    Redemptions,
+    Borrows,
+    Repayments,
+    Withdrawals,
+    Signatures,
+}
+
+impl PauseFlag {
+    /// Returns the bit representing this flag in [`PauseFlags`].
+    const fn bit(self) -> u8 {
+        match self {
+            PauseFlag::Deposits => 1 << 0,
+            PauseFlag::Redemptions => 1 << 1,
+            PauseFlag::Borrows => 1 << 2,
+            PauseFlag::Repayments => 1 << 3,
+            PauseFlag::Withdrawals => 1 << 4,
+            PauseFlag::Signatures => 1 << 5,
+        }
+    }
+}
+
+/// Bitset of paused operation classes.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PauseFlags(u8);
+
+impl PauseFlags {
+    /// No operations paused.
+    pub const NONE: PauseFlags = PauseFlags(0);
+    /// Every operation paused.
+    pub const ALL: PauseFlags = PauseFlags(
+        PauseFlag::Deposits.bit()
+            | PauseFlag::Redemptions.bit()
+            | PauseFlag::Borrows.bit()
+            | PauseFlag::Repayments.bit()
+            | PauseFlag::Withdrawals.bit()
+            | PauseFlag::Signatures.bit(),
+    );
+
+    /// Returns whether `flag` is set.
+    pub fn contains(self, flag: PauseFlag) -> bool {
+        self.0 & flag.bit() != 0
+    }
+
+    /// Returns whether every flag is set.
+    pub fn is_all(self) -> bool {
+        self.0 & Self::ALL.0 == Self::ALL.0
+    }
+
+    /// Sets or clears `flag`.
+    pub fn set(&mut self, flag: PauseFlag, paused: bool) {
+        if paused {
+            self.0 |= flag.bit();
+        } else {
+            self.0 &= !flag.bit();
+        }
+    }
+}
+
+impl Contract {
+    /// Asserts that the caller is the owner or a guardian.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the predecessor is neither the owner nor a guardian.
+    pub(crate) fn require_owner_or_guardian(&self) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.owner_id || self.guardians.contains(&caller),
+            "Only the owner or a guardian can call this method"
+        );
+    }
+
+    /// Asserts that operations guarded by `flag` are not paused.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `flag` is set.
+    pub(crate) fn require_not_paused_for(&self, flag: PauseFlag) {
+        if self.pause_flags.contains(flag) {
+            env::panic_str(&format!("{:?} are paused", flag));
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Pauses or unpauses a single operation class.
+    ///
+    /// # Arguments
+    ///
+    /// * `flag` - The operation class to toggle
+    /// * `paused` - Whether the operation class should be paused
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the owner or a guardian.
+    pub fn set_paused(&mut self, flag: PauseFlag, paused: bool) {
+        self.require_owner_or_guardian();
+        self.pause_flags.set(flag, paused);
+    }
+
+    /// Pauses every operation, blocking all state-changing methods.
+    ///
+    /// View methods remain accessible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the owner or a guardian.
+    pub fn pause_all(&mut self) {
+        self.require_owner_or_guardian();
+        self.pause_flags = PauseFlags::ALL;
+    }
+
+    /// Clears every pause flag, resuming normal operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the owner or a guardian.
+    pub fn unpause_all(&mut self) {
+        self.require_owner_or_guardian();
+        self.pause_flags = PauseFlags::NONE;
+    }
+
+    /// Pauses every operation. Equivalent to `pause_all`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the owner or a guardian.
+    pub fn pause(&mut self) {
+        self.pause_all();
+    }
+
+    /// Clears every pause flag. Equivalent to `unpause_all`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the owner or a guardian.
+    pub fn unpause(&mut self) {
+        self.unpause_all();
+    }
+
+    /// Grants the guardian role to an account.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn add_guardian(&mut self, account_id: AccountId) {
+        self.require_owner();
+        self.guardians.insert(account_id);
+    }
+
+    /// Revokes the guardian role from an account.
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn remove_guardian(&mut self, account_id: AccountId) {
+        self.require_owner();
+        self.guardians.remove(&account_id);
+    }
+
+    /// Returns whether every operation is paused.
+    pub fn is_paused(&self) -> bool {
+        self.pause_flags.is_all()
+    }
+
+    /// Returns the bitset of paused operation classes.
+    pub fn get_pause_flags(&self) -> PauseFlags {
+        self.pause_flags
+    }
+
+    /// Returns whether an account holds the guardian role.
+    pub fn is_guardian(&self, account_id: AccountId) -> bool {
+        self.guardians.contains(&account_id)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intents::State;
+    use crate::test_utils::builders::ContractBuilder;
+    use crate::test_utils::helpers::init_ctx;
+    use near_sdk::json_types::U128;
+
+    fn pause_borrows(contract: &mut Contract) {
+        init_ctx("owner.test", 0);
+        contract.set_paused(PauseFlag::Borrows, true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Borrows are paused")]
+    fn borrow_flag_blocks_new_intent() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .build();
+        pause_borrows(&mut contract);
+
+        init_ctx("solver.test", 1);
+        contract.new_intent(
+            "intent".to_string(),
+            "solver.deposit".parse().unwrap(),
+            "hash-1".to_string(),
+            U128(1_000_000),
+        );
+    }
+
+    #[test]
+    fn borrow_flag_leaves_redemptions_running() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(50_000)
+            .build();
+        let user: AccountId = "alice.test".parse().unwrap();
+        contract.token.internal_register_account(&user);
+        contract.token.internal_deposit(&user, 100_000_000);
+        contract.enqueue_redemption(user.clone(), user, 50_000_000, 20_000, None, None, 0);
+        pause_borrows(&mut contract);
+
+        init_ctx("keeper.test", 0);
+        assert!(contract.process_next_redemption());
+        assert_eq!(contract.total_assets, 30_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn pause_all_blocks_unflagged_methods() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        init_ctx("owner.test", 0);
+        contract.pause_all();
+        assert!(contract.pause_flags.is_all());
+        contract.approve_codehash("codehash".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Redemptions are paused")]
+    fn redemption_flag_blocks_share_transfers() {
+        use near_contract_standards::fungible_token::core::FungibleTokenCore;
+
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .supply(1_000_000)
+            .build();
+        contract.set_paused(PauseFlag::Redemptions, true);
+
+        init_ctx("owner.test", 1);
+        contract.ft_transfer("alice.test".parse().unwrap(), U128(1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Signatures are paused")]
+    fn signature_flag_blocks_agent_registration() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        contract.set_paused(PauseFlag::Signatures, true);
+
+        init_ctx("agent.test", 0);
+        contract.register_agent("codehash".to_string());
+    }
+
+    /// Builds a vault where `solver.test` holds intent 0 and `flag` is paused.
+    fn paused_with_intent(flag: PauseFlag) -> Contract {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(10_000_000)
+            .intent("solver.test", "hash-1", 1_000_000)
+            .build();
+        contract.set_paused(flag, true);
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "Borrows are paused")]
+    fn borrow_flag_blocks_intent_state_update() {
+        let mut contract = paused_with_intent(PauseFlag::Borrows);
+        init_ctx("solver.test", 0);
+        contract.update_intent_state(0, State::SwapCompleted);
+    }
+
+    #[test]
+    #[should_panic(expected = "Borrows are paused")]
+    fn borrow_flag_blocks_batched_intent_state_update() {
+        let mut contract = paused_with_intent(PauseFlag::Borrows);
+        init_ctx("solver.test", 0);
+        contract.update_intent_states(vec![(0, State::SwapCompleted)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Signatures are paused")]
+    fn signature_flag_blocks_adding_public_keys() {
+        let mut contract = paused_with_intent(PauseFlag::Signatures);
+        let _ = contract.add_public_key("ed25519:key".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Signatures are paused")]
+    fn signature_flag_blocks_removing_public_keys() {
+        let mut contract = paused_with_intent(PauseFlag::Signatures);
+        let _ = contract.remove_public_key("ed25519:key".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Deposits are paused")]
+    fn deposit_flag_blocks_transfer_deposits() {
+        use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+
+        let mut contract = paused_with_intent(PauseFlag::Deposits);
+        init_ctx("usdc.test", 0);
+        let msg = serde_json::json!({ "deposit": {} }).to_string();
+        let _ = contract.ft_on_transfer("alice.test".parse().unwrap(), U128(1_000_000), msg);
+    }
+
+    #[test]
+    #[should_panic(expected = "Repayments are paused")]
+    fn repayment_flag_blocks_transfer_repayments() {
+        use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+
+        let mut contract = paused_with_intent(PauseFlag::Repayments);
+        init_ctx("usdc.test", 0);
+        let msg = serde_json::json!({ "repay": { "intent_index": "0" } }).to_string();
+        let _ = contract.ft_on_transfer("solver.test".parse().unwrap(), U128(1_010_000), msg);
+    }
+
+    #[test]
+    fn pause_and_unpause_toggle_every_flag() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        contract.pause();
+        assert_eq!(contract.pause_flags, PauseFlags::ALL);
+        contract.unpause();
+        assert_eq!(contract.pause_flags, PauseFlags::NONE);
+    }
+
+    #[test]
+    fn pause_views_track_flags() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        init_ctx("owner.test", 0);
+        assert!(!contract.is_paused());
+        assert_eq!(contract.get_pause_flags(), PauseFlags::NONE);
+
+        contract.set_paused(PauseFlag::Borrows, true);
+        assert!(!contract.is_paused());
+        assert!(contract.get_pause_flags().contains(PauseFlag::Borrows));
+        assert_eq!(
+            contract.get_vault_config().pause_flags,
+            contract.get_pause_flags()
+        );
+
+        contract.pause_all();
+        assert!(contract.is_paused());
+        assert!(contract.get_vault_config().paused);
+
+        contract.unpause_all();
+        assert!(!contract.is_paused());
+        assert!(!contract.get_vault_config().paused);
+    }
+
+    fn add_guardian(contract: &mut Contract) {
+        init_ctx("owner.test", 0);
+        contract.add_guardian("guardian.test".parse().unwrap());
+        assert!(contract.is_guardian("guardian.test".parse().unwrap()));
+    }
+
+    #[test]
+    fn guardian_can_pause_and_unpause() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        add_guardian(&mut contract);
+
+        init_ctx("guardian.test", 0);
+        contract.set_paused(PauseFlag::Borrows, true);
+        assert!(contract.pause_flags.contains(PauseFlag::Borrows));
+        contract.pause_all();
+        assert!(contract.pause_flags.is_all());
+        contract.unpause_all();
+        assert_eq!(contract.pause_flags, PauseFlags::NONE);
+    }
+
+    #[test]
+    #[should_panic]
+    fn guardian_cannot_set_solver_fee() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        add_guardian(&mut contract);
+
+        init_ctx("guardian.test", 0);
+        contract.set_solver_fee(50);
+    }
+
+    #[test]
+    #[should_panic]
+    fn guardian_cannot_set_deposit_cap() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        add_guardian(&mut contract);
+
+        init_ctx("guardian.test", 0);
+        contract.set_deposit_cap(Some(U128(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner or a guardian can call this method")]
+    fn removed_guardian_cannot_pause() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        add_guardian(&mut contract);
+        contract.remove_guardian("guardian.test".parse().unwrap());
+
+        init_ctx("guardian.test", 0);
+        contract.pause_all();
+    }
+
+    #[test]
+    fn unpause_all_clears_every_flag() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        init_ctx("owner.test", 0);
+        contract.set_paused(PauseFlag::Deposits, true);
+        contract.set_paused(PauseFlag::Signatures, true);
+        assert!(contract.pause_flags.contains(PauseFlag::Deposits));
+        assert!(!contract.pause_flags.contains(PauseFlag::Borrows));
+
+        contract.set_paused(PauseFlag::Deposits, false);
+        assert!(!contract.pause_flags.contains(PauseFlag::Deposits));
+
+        contract.unpause_all();
+        assert_eq!(contract.pause_flags, PauseFlags::NONE);
+    }
+}
@@ -0,0 +1,168 @@
+//! # Token Recovery Module
+//!
+//! Lets the owner return NEP-141 tokens that were sent to the vault with a
+//! plain `ft_transfer` (which never reaches `ft_on_transfer`) and would
+//! otherwise be stuck. The vault's own asset can never be recovered: every
+//! unit of it backs shares, borrows or queued redemptions.
+
+use crate::vault_standards::events::TokensRecovered;
+use crate::*;
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_sdk::{ext_contract, PromiseResult};
+
+/// Callback interface for token recovery.
+#[allow(dead_code)]
+#[ext_contract(ext_self)]
+trait ExtContract {
+    fn on_tokens_recovered(&mut self, token: AccountId, recipient: AccountId, amount: U128)
+        -> U128;
+}
+
+#[near]
+impl Contract {
+    /// Transfers stray tokens of a non-asset NEP-141 out of the vault.
+    ///
+    /// The recipient must be registered with `token`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The stray token contract
+    /// * `recipient` - Account to send the tokens to
+    /// * `amount` - Amount to transfer
+    ///
+    /// # Returns
+    ///
+    /// A promise resolving to the amount recovered (0 if the transfer failed).
+    ///
+    /// # Panics
+    ///
+    /// - If caller is not the contract owner
+    /// - If `token` is the vault's asset
+    /// - If `amount` is zero
+    pub fn recover_tokens(
+        &mut self,
+        token: AccountId,
+        recipient: AccountId,
+        amount: U128,
+    ) -> Promise {
+        self.require_owner();
+        require!(token != self.asset, "Cannot recover the vault asset");
+        require!(amount.0 > 0, "Amount must be greater than 0");
+
+        ext_ft_core::ext(token.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(self.gas_config.withdraw_transfer)
+            .ft_transfer(
+                recipient.clone(),
+                amount,
+                Some("Token recovery".to_string()),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(self.gas_config.withdraw_callback)
+                    .on_tokens_recovered(token, recipient, amount),
+            )
+    }
+
+    /// Callback after transferring recovered tokens.
+    ///
+    /// Emits `TokensRecovered` if the transfer succeeded. Nothing is tracked
+    /// for stray tokens, so a failure needs no rollback.
+    #[private]
+    pub fn on_tokens_recovered(
+        &mut self,
+        token: AccountId,
+        recipient: AccountId,
+        amount: U128,
+    ) -> U128 {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                TokensRecovered {
+                    token: &token,
+                    recipient: &recipient,
+                    amount,
+                }
+                .emit(self.event_standard());
+                amount
+            }
+            _ => U128(0),
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::builders::ContractBuilder;
+    use crate::test_utils::helpers::{init_callback_ctx, init_ctx};
+    use near_sdk::test_utils::{get_created_receipts, get_logs};
+
+    #[test]
+    fn recover_tokens_transfers_stray_token() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(1_000_000)
+            .build();
+        init_ctx("owner.test", 0);
+        let _ = contract.recover_tokens(
+            "dai.test".parse().unwrap(),
+            "alice.test".parse().unwrap(),
+            U128(500),
+        );
+        assert_eq!(contract.total_assets, 1_000_000);
+
+        let transfer = get_created_receipts()
+            .into_iter()
+            .find(|receipt| receipt.receiver_id.as_str() == "dai.test")
+            .expect("ft_transfer receipt");
+        match &transfer.actions[0] {
+            near_sdk::mock::MockAction::FunctionCallWeight {
+                method_name, args, ..
+            } => {
+                assert_eq!(method_name, b"ft_transfer");
+                let args: serde_json::Value = serde_json::from_slice(args).unwrap();
+                assert_eq!(args["receiver_id"], "alice.test");
+                assert_eq!(args["amount"], "500");
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+
+        init_callback_ctx(PromiseResult::Successful(vec![]));
+        let recovered = contract.on_tokens_recovered(
+            "dai.test".parse().unwrap(),
+            "alice.test".parse().unwrap(),
+            U128(500),
+        );
+        assert_eq!(recovered, U128(500));
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains("\"tokens_recovered\"") && log.contains("dai.test")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot recover the vault asset")]
+    fn recover_tokens_rejects_vault_asset() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        init_ctx("owner.test", 0);
+        let _ = contract.recover_tokens(
+            "usdc.test".parse().unwrap(),
+            "owner.test".parse().unwrap(),
+            U128(1),
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn recover_tokens_is_owner_only() {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test").build();
+        init_ctx("alice.test", 0);
+        let _ = contract.recover_tokens(
+            "dai.test".parse().unwrap(),
+            "alice.test".parse().unwrap(),
+            U128(1),
+        );
+    }
+}
@@ -15,7 +15,7 @@ pub mod helpers {
     use crate::Contract;
     use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
     use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::{testing_env, NearToken};
+    use near_sdk::{testing_env, NearToken, PromiseResult, RuntimeFeesConfig};
 
     /// Initializes the NEAR VM context for testing.
     ///
@@ -41,6 +41,27 @@ pub mod helpers {
         testing_env!(builder.build());
     }
 
+    /// Initializes the NEAR VM context for a `#[private]` callback.
+    ///
+    /// The predecessor is set to the contract account itself and the
+    /// given promise result is made available via `env::promise_result(0)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The outcome of the promise the callback resolves
+    pub fn init_callback_ctx(result: PromiseResult) {
+        let mut builder = VMContextBuilder::new();
+        let current = builder.context.current_account_id.clone();
+        builder.predecessor_account_id(current);
+        testing_env!(
+            builder.build(),
+            near_sdk::test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+    }
+
     /// Initializes a contract with default settings (3 extra decimals).
     ///
     /// # Arguments
@@ -75,12 +96,13 @@ pub mod helpers {
             icon: None,
             reference: None,
             reference_hash: None,
-            decimals: 24,
+            decimals: 6 + extra_decimals,
         };
         Contract::init(
             owner.parse().unwrap(),
             asset.parse().unwrap(),
             metadata,
+            6,
             extra_decimals,
             1, // 1% solver fee
         )
@@ -93,6 +115,7 @@ pub mod builders {
     use crate::test_utils::helpers::init_ctx;
     use crate::Contract;
     use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+    use near_sdk::json_types::U128;
 
     /// Builder for creating test `Contract` instances with custom configuration.
     ///
@@ -103,6 +126,7 @@ pub mod builders {
     /// ```ignore
     /// let contract = ContractBuilder::new("owner.test", "usdc.test")
     ///     .total_assets(1_000_000)
+    ///     .intent("solver.test", "hash-1", 400_000)
     ///     .predecessor("solver.test")
     ///     .attached(1)
     ///     .build();
@@ -113,6 +137,7 @@ pub mod builders {
         extra: u8,
         total_assets: u128,
         supply: u128,
+        intents: Vec<(String, String, u128)>,
         predecessor: Option<String>,
         attached: u128,
     }
@@ -131,6 +156,7 @@ pub mod builders {
                 extra: 3,
                 total_assets: 0,
                 supply: 0,
+                intents: Vec::new(),
                 predecessor: Some(owner.to_string()),
                 attached: 0,
             }
@@ -154,6 +180,16 @@ pub mod builders {
             self
         }
 
+        /// Opens a borrowed intent for `solver` under `hash`.
+        ///
+        /// The `amount` is lent out of `total_assets`, so the vault's
+        /// borrowed and idle totals stay consistent with the intent.
+        pub fn intent(mut self, solver: &str, hash: &str, amount: u128) -> Self {
+            self.intents
+                .push((solver.to_string(), hash.to_string(), amount));
+            self
+        }
+
         /// Sets the predecessor (caller) account for subsequent calls.
         pub fn predecessor(mut self, id: &str) -> Self {
             self.predecessor = Some(id.to_string());
@@ -178,20 +214,36 @@ pub mod builders {
                 icon: None,
                 reference: None,
                 reference_hash: None,
-                decimals: 24,
+                decimals: 6 + self.extra,
             };
             let mut c = Contract::init(
                 self.owner.parse().unwrap(),
                 self.asset.parse().unwrap(),
                 meta,
+                6,
                 self.extra,
                 1, // 1% solver fee
             );
             if self.supply > 0 {
-                c.token
-                    .internal_deposit(&self.owner.parse().unwrap(), self.supply);
+                let owner = self.owner.parse().unwrap();
+                c.token.internal_register_account(&owner);
+                c.token.internal_deposit(&owner, self.supply);
             }
             c.total_assets = self.total_assets;
+            for (solver, hash, amount) in self.intents {
+                c.insert_intent(
+                    solver.parse().unwrap(),
+                    "intent".to_string(),
+                    hash,
+                    U128(amount),
+                    false,
+                    c.solver_fee,
+                );
+                c.total_assets = c
+                    .total_assets
+                    .checked_sub(amount)
+                    .expect("intent exceeds total_assets");
+            }
             c
         }
     }
@@ -6,15 +6,40 @@
 //! ## Usage
 //!
 //! To upgrade the contract, call `update_contract` with the new WASM code
-//! as the function input (not as an argument).
+//! as the function input (not as an argument). The deploy is batched with a
+//! call to `migrate`, so the new code never runs against unmigrated state.
+//! Every release's `OldContract` must therefore describe the layout it
+//! replaces, even when that layout is unchanged.
 //!
 //! Example using NEAR CLI:
 //! ```bash
 //! near call <contract_id> update_contract --base64-file <path_to_wasm> --accountId <owner_id>
 //! ```
+//!
+//! ## State Migration
+//!
+//! When a release changes the `Contract` storage layout, the new code must be
+//! deployed together with a call to `migrate`. `OldContract` mirrors the layout
+//! that is currently deployed; `migrate` reads it, copies every existing field
+//! and fills defaults for fields added since.
+//!
+//! When adding a field to `Contract`:
+//! 1. Leave `OldContract` unchanged (it describes what is on-chain)
+//! 2. Give the new field a default in `migrate`
+//! 3. After the migrated release is live everywhere, update `OldContract` to
+//!    the new layout before the next layout change
+//!
+//! Example using NEAR CLI:
+//! ```bash
+//! near deploy <contract_id> <path_to_wasm> --initFunction migrate --initArgs '{}'
+//! ```
 
+use crate::intents::State;
 use crate::*;
 
+/// Gas attached to the `migrate` call batched with the code deploy.
+const GAS_FOR_MIGRATE: Gas = Gas::from_tgas(100);
+
 #[near]
 impl Contract {
     /// Upgrades the contract code to a new version.
@@ -28,7 +53,8 @@ impl Contract {
     ///
     /// # Returns
     ///
-    /// A promise that deploys the new contract code.
+    /// A promise that deploys the new contract code and then runs `migrate`
+    /// in the same batch. If `migrate` fails, the deploy is reverted too.
     ///
     /// # Panics
     ///
@@ -41,6 +67,475 @@ impl Contract {
 
         Promise::new(env::current_account_id())
             .deploy_contract(code)
+            .function_call(
+                "migrate".to_string(),
+                vec![],
+                NearToken::from_yoctonear(0),
+                GAS_FOR_MIGRATE,
+            )
             .as_return()
     }
 }
+
+// ============================================================================
+// Version Information
+// ============================================================================
+
+/// Semantic version of this build, taken from `Cargo.toml`.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Build details returned by `build_info`.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildInfo {
+    /// Crate version, as returned by `version`.
+    pub version: String,
+    /// Crate name.
+    pub package: String,
+    /// Source repository the build was made from.
+    pub repository: String,
+}
+
+#[near]
+impl Contract {
+    /// Returns the semantic version of the deployed code.
+    ///
+    /// Compare it before and after `update_contract` to confirm which build
+    /// is live.
+    pub fn version(&self) -> String {
+        VERSION.to_string()
+    }
+
+    /// Returns the version together with the package it was built from.
+    pub fn build_info(&self) -> BuildInfo {
+        BuildInfo {
+            version: VERSION.to_string(),
+            package: env!("CARGO_PKG_NAME").to_string(),
+            repository: env!("CARGO_PKG_REPOSITORY").to_string(),
+        }
+    }
+}
+
+// ============================================================================
+// State Migration
+// ============================================================================
+
+/// Storage layout of a queued redemption in the deployed contract (v0.1.0).
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct OldPendingRedemption {
+    pub owner_id: AccountId,
+    pub receiver_id: AccountId,
+    pub shares: u128,
+    pub assets: u128,
+    pub memo: Option<String>,
+}
+
+/// Storage layout of an open intent in the deployed contract (v0.1.0).
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct OldIntent {
+    pub created: U64,
+    pub state: State,
+    pub intent_data: String,
+    pub user_deposit_hash: String,
+    pub borrow_amount: U128,
+    pub repayment_amount: Option<U128>,
+}
+
+/// Storage layout of a registered worker in the deployed contract (v0.1.0).
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct OldWorker {
+    pub codehash: String,
+}
+
+/// Storage layout of the currently deployed contract (v0.1.0).
+#[near(serializers = [borsh])]
+pub struct OldContract {
+    pub owner_id: AccountId,
+    pub is_paused: bool,
+    pub approved_codehashes: IterableSet<String>,
+    pub approved_solvers: IterableSet<AccountId>,
+    pub worker_by_account_id: IterableMap<AccountId, OldWorker>,
+    pub solver_id_to_indices: IterableMap<AccountId, Vec<u128>>,
+    pub index_to_intent: IterableMap<u128, OldIntent>,
+    pub intent_nonce: u128,
+    pub token: FungibleToken,
+    pub metadata: FungibleTokenMetadata,
+    pub asset: AccountId,
+    pub total_assets: u128,
+    pub total_borrowed: u128,
+    pub extra_decimals: u8,
+    pub solver_fee: u8,
+    pub pending_redemptions: Vector<OldPendingRedemption>,
+    pub pending_redemptions_head: u32,
+}
+
+#[near]
+impl Contract {
+    /// Migrates state from the `OldContract` layout to the current one.
+    ///
+    /// Existing fields are carried over unchanged; fields added since the
+    /// deployed release receive their defaults.
+    ///
+    /// # Access Control
+    ///
+    /// Only callable by the contract account itself (e.g. as the init call
+    /// of a deploy transaction).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stored state cannot be read as `OldContract`.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let mut old: OldContract = env::state_read().expect("Failed to read old state");
+
+        // Queue entries gained a storage deposit; rewrite the unprocessed
+        // ones under the same prefix. Their deposit is 0 since none was paid.
+        let queued: Vec<OldPendingRedemption> = (old.pending_redemptions_head
+            ..old.pending_redemptions.len())
+            .filter_map(|index| old.pending_redemptions.get(index).cloned())
+            .collect();
+        old.pending_redemptions.clear();
+        old.pending_redemptions.flush();
+        let mut pending_redemptions = Vector::new(StorageKey::PendingRedemptions);
+        for entry in queued {
+            pending_redemptions.push(PendingRedemption {
+                owner_id: entry.owner_id,
+                receiver_id: entry.receiver_id,
+                shares: entry.shares,
+                assets: entry.assets,
+                memo: entry.memo,
+                storage_deposit: 0,
+                min_assets_out: None,
+            });
+        }
+
+        // Intents gained the `data_committed` flag and a solver fee snapshot;
+        // every stored intent held its payload inline and was borrowed at the
+        // current fee. Rewrite them under the same prefix and index them by
+        // deposit hash for lookups.
+        let intents: Vec<(u128, OldIntent)> = old
+            .index_to_intent
+            .iter()
+            .map(|(index, intent)| (*index, intent.clone()))
+            .collect();
+        old.index_to_intent.clear();
+        old.index_to_intent.flush();
+        let mut index_to_intent = IterableMap::new(StorageKey::IndexToIntent);
+        let mut hash_to_intent_index = IterableMap::new(StorageKey::HashToIntentIndex);
+        let mut open_intent_fees = 0u128;
+        for (index, intent) in intents {
+            open_intent_fees = open_intent_fees.saturating_add(Contract::internal_solver_fee(
+                intent.borrow_amount.0,
+                old.solver_fee,
+            ));
+            hash_to_intent_index.insert(intent.user_deposit_hash.clone(), index);
+            index_to_intent.insert(
+                index,
+                Intent {
+                    created: intent.created,
+                    state: intent.state,
+                    intent_data: intent.intent_data,
+                    data_committed: false,
+                    user_deposit_hash: intent.user_deposit_hash,
+                    borrow_amount: intent.borrow_amount,
+                    solver_fee: old.solver_fee,
+                    repayment_amount: intent.repayment_amount,
+                },
+            );
+        }
+
+        // Workers gained a registration deposit and codehash change window.
+        // Agents registered before paid no deposit, so none is refunded.
+        let workers: Vec<(AccountId, OldWorker)> = old
+            .worker_by_account_id
+            .iter()
+            .map(|(account_id, worker)| (account_id.clone(), worker.clone()))
+            .collect();
+        old.worker_by_account_id.clear();
+        old.worker_by_account_id.flush();
+        let mut worker_by_account_id = IterableMap::new(StorageKey::WorkerByAccountId);
+        for (account_id, worker) in workers {
+            worker_by_account_id.insert(
+                account_id,
+                Worker {
+                    codehash: worker.codehash,
+                    deposit: NearToken::from_yoctonear(0),
+                    codehash_changes: 0,
+                    window_start: U64(0),
+                },
+            );
+        }
+
+        // The asset's decimals were implicit in the share metadata before.
+        let asset_decimals = old.metadata.decimals.saturating_sub(old.extra_decimals);
+
+        Self {
+            owner_id: old.owner_id,
+            pause_flags: if old.is_paused {
+                PauseFlags::ALL
+            } else {
+                PauseFlags::NONE
+            },
+            approved_codehashes: old.approved_codehashes,
+            approved_solvers: old.approved_solvers,
+            worker_by_account_id,
+            solver_id_to_indices: old.solver_id_to_indices,
+            index_to_intent,
+            intent_nonce: old.intent_nonce,
+            token: old.token,
+            metadata: old.metadata,
+            asset: old.asset,
+            total_assets: old.total_assets,
+            total_borrowed: old.total_borrowed,
+            extra_decimals: old.extra_decimals,
+            solver_fee: old.solver_fee,
+            pending_redemptions,
+            pending_redemptions_head: 0,
+            locked_collateral: IterableMap::new(StorageKey::LockedCollateral),
+            registered_public_keys: IterableSet::new(StorageKey::RegisteredPublicKeys),
+            verbose_logging: false,
+            allowed_receivers: None,
+            pending_owner: None,
+            guardians: IterableSet::new(StorageKey::Guardians),
+            deposit_cap: None,
+            queue_compaction_batch: DEFAULT_QUEUE_COMPACTION_BATCH,
+            total_donated: 0,
+            keepers: IterableSet::new(StorageKey::Keepers),
+            enforce_keeper: false,
+            completed_intents: Vector::new(StorageKey::CompletedIntents),
+            completed_intents_cursor: 0,
+            history_capacity: DEFAULT_HISTORY_CAPACITY,
+            borrow_in_flight: false,
+            asset_decimals,
+            min_first_deposit: 10u128.pow(asset_decimals as u32),
+            hash_to_intent_index,
+            gas_config: GasConfig::default(),
+            pending_borrowed: 0,
+            borrow_cooldown_blocks: 0,
+            last_redemption_block: 0,
+            max_intent_data_len: DEFAULT_MAX_INTENT_DATA_LEN,
+            max_hash_len: DEFAULT_MAX_HASH_LEN,
+            protocol_fee_bps: 0,
+            treasury_id: None,
+            accrued_protocol_fees: 0,
+            max_memo_len: DEFAULT_MAX_MEMO_LEN,
+            deposit_rounding: Rounding::Down,
+            max_intents_per_solver: None,
+            failed_borrows: Vector::new(StorageKey::FailedBorrows),
+            failed_borrows_cursor: 0,
+            keeper_reward_bps: 0,
+            total_keeper_rewards: 0,
+            burn_on_queue: false,
+            queue_escrow: LookupMap::new(StorageKey::QueueEscrow),
+            cumulative_yield: 0,
+            yield_window_start: 0,
+            total_written_off: 0,
+            origination_fee_bps: 0,
+            share_transfer_blocklist: IterableSet::new(StorageKey::ShareTransferBlocklist),
+            min_reserve: 0,
+            share_allowances: IterableMap::new(StorageKey::ShareAllowances),
+            event_standard: DEFAULT_EVENT_STANDARD.to_string(),
+            event_version: DEFAULT_EVENT_VERSION.to_string(),
+            chain_config: IterableMap::new(StorageKey::ChainConfig),
+            max_account_shares: None,
+            min_share_balance: None,
+            blocked_solvers: IterableSet::new(StorageKey::BlockedSolvers),
+            intent_cancel_window_ns: DEFAULT_INTENT_CANCEL_WINDOW_NS,
+            loss_payout: None,
+            open_intent_fees,
+            keeper_reward_budget: 0,
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::init_ctx;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_sdk::mock::MockAction;
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+
+    fn old_state() -> OldContract {
+        let owner: AccountId = "owner.test".parse().unwrap();
+        let mut token = FungibleToken::new(StorageKey::FungibleToken);
+        token.internal_register_account(&owner);
+        token.internal_deposit(&owner, 7_000);
+
+        OldContract {
+            owner_id: owner,
+            is_paused: true,
+            approved_codehashes: IterableSet::new(StorageKey::ApprovedCodehashes),
+            approved_solvers: IterableSet::new(StorageKey::ApprovedSolvers),
+            worker_by_account_id: IterableMap::new(StorageKey::WorkerByAccountId),
+            solver_id_to_indices: IterableMap::new(StorageKey::SolverIdToIndices),
+            index_to_intent: IterableMap::new(StorageKey::IndexToIntent),
+            intent_nonce: 4,
+            token,
+            metadata: FungibleTokenMetadata {
+                spec: "ft-1.0.0".to_string(),
+                name: "USDC Vault Shares".to_string(),
+                symbol: "vUSDC".to_string(),
+                icon: None,
+                reference: None,
+                reference_hash: None,
+                decimals: 24,
+            },
+            asset: "usdc.test".parse().unwrap(),
+            total_assets: 5_000_000,
+            total_borrowed: 2_000_000,
+            extra_decimals: 3,
+            solver_fee: 1,
+            pending_redemptions: Vector::new(StorageKey::PendingRedemptions),
+            pending_redemptions_head: 0,
+        }
+    }
+
+    #[test]
+    fn migrate_preserves_old_fields_and_defaults_new_ones() {
+        init_ctx("alice.near", 0);
+        let mut old = old_state();
+        old.approved_codehashes.insert("codehash".to_string());
+        old.approved_codehashes.flush();
+        env::state_write(&old);
+
+        let contract = Contract::migrate();
+
+        assert_eq!(contract.owner_id.as_str(), "owner.test");
+        assert!(contract.pause_flags.is_all());
+        assert_eq!(contract.intent_nonce, 4);
+        assert_eq!(contract.total_assets, 5_000_000);
+        assert_eq!(contract.total_borrowed, 2_000_000);
+        assert!(contract.approved_codehashes.contains("codehash"));
+        assert_eq!(
+            contract.ft_balance_of("owner.test".parse().unwrap()).0,
+            7_000
+        );
+
+        assert!(contract.locked_collateral.is_empty());
+        assert!(contract.registered_public_keys.is_empty());
+        assert!(!contract.verbose_logging);
+        assert!(contract.allowed_receivers.is_none());
+    }
+
+    #[test]
+    fn migrate_rewrites_unprocessed_queue_entries() {
+        init_ctx("alice.near", 0);
+        let mut old = old_state();
+        for name in ["a.test", "b.test", "c.test"] {
+            old.pending_redemptions.push(OldPendingRedemption {
+                owner_id: name.parse().unwrap(),
+                receiver_id: name.parse().unwrap(),
+                shares: 1_000,
+                assets: 1,
+                memo: None,
+            });
+        }
+        old.pending_redemptions.flush();
+        old.pending_redemptions_head = 1;
+        env::state_write(&old);
+
+        let contract = Contract::migrate();
+
+        assert_eq!(contract.pending_redemptions_head, 0);
+        assert_eq!(contract.pending_redemptions.len(), 2);
+        let first = contract.pending_redemptions.get(0).unwrap();
+        assert_eq!(first.owner_id.as_str(), "b.test");
+        assert_eq!(first.storage_deposit, 0);
+        assert_eq!(
+            contract
+                .pending_redemptions
+                .get(1)
+                .unwrap()
+                .owner_id
+                .as_str(),
+            "c.test"
+        );
+    }
+
+    #[test]
+    fn migrate_rewrites_open_intents_as_inline() {
+        init_ctx("alice.near", 0);
+        let mut old = old_state();
+        old.index_to_intent.insert(
+            3,
+            OldIntent {
+                created: U64(1),
+                state: State::StpLiquidityDeposited,
+                intent_data: "payload".to_string(),
+                user_deposit_hash: "hash-3".to_string(),
+                borrow_amount: U128(2_000_000),
+                repayment_amount: None,
+            },
+        );
+        old.index_to_intent.flush();
+        env::state_write(&old);
+
+        let contract = Contract::migrate();
+
+        let intent = contract.index_to_intent.get(&3).unwrap();
+        assert_eq!(intent.intent_data, "payload");
+        assert!(!intent.data_committed);
+        assert_eq!(intent.state, State::StpLiquidityDeposited);
+        assert_eq!(intent.solver_fee, contract.solver_fee);
+        assert_eq!(contract.hash_to_intent_index.get("hash-3"), Some(&3));
+        assert_eq!(contract.open_intent_fees, 20_000);
+    }
+
+    #[test]
+    fn migrate_keeps_workers_without_deposit() {
+        init_ctx("alice.near", 0);
+        let mut old = old_state();
+        old.worker_by_account_id.insert(
+            "worker.test".parse().unwrap(),
+            OldWorker {
+                codehash: "abc".to_string(),
+            },
+        );
+        old.worker_by_account_id.flush();
+        env::state_write(&old);
+
+        let contract = Contract::migrate();
+
+        let worker = contract.get_agent("worker.test".parse().unwrap());
+        assert_eq!(worker.codehash, "abc");
+        assert!(worker.deposit.is_zero());
+    }
+
+    #[test]
+    fn update_contract_batches_migrate_after_deploy() {
+        let contract = crate::test_utils::helpers::init_contract("owner.test", "usdc.test");
+        let mut context = VMContextBuilder::new()
+            .current_account_id("vault.test".parse().unwrap())
+            .predecessor_account_id("owner.test".parse().unwrap())
+            .build();
+        context.input = vec![0u8; 4].into();
+        near_sdk::testing_env!(context);
+        let _ = contract.update_contract();
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        let actions = &receipts[0].actions;
+        assert!(matches!(actions[0], MockAction::DeployContract { .. }));
+        assert!(matches!(
+            &actions[1],
+            MockAction::FunctionCallWeight { method_name, .. } if method_name == b"migrate"
+        ));
+    }
+
+    #[test]
+    fn version_reports_crate_version() {
+        let contract = crate::test_utils::helpers::init_contract("owner.test", "usdc.test");
+        assert_eq!(contract.version(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(contract.build_info().version, contract.version());
+        assert_eq!(contract.get_vault_config().version, contract.version());
+    }
+}
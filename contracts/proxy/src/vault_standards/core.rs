@@ -20,13 +20,19 @@
 
 use near_contract_standards::fungible_token::{receiver::FungibleTokenReceiver, FungibleTokenCore};
 use near_sdk::{json_types::U128, AccountId, PromiseOrValue};
-use uint::construct_uint;
 
-construct_uint! {
-    /// 256-bit unsigned integer for overflow-safe arithmetic.
-    pub struct U256(4);
+#[allow(clippy::manual_div_ceil)]
+mod u256 {
+    use uint::construct_uint;
+
+    construct_uint! {
+        /// 256-bit unsigned integer for overflow-safe arithmetic.
+        pub struct U256(4);
+    }
 }
 
+pub use u256::U256;
+
 /// Core vault trait following NEP-621 Fungible Token Vault standard.
 ///
 /// Implementors must provide deposit and withdrawal logic while inheriting
@@ -46,11 +52,13 @@ pub trait VaultCore: FungibleTokenCore + FungibleTokenReceiver {
     /// * `shares` - Number of shares to redeem
     /// * `receiver_id` - Account to receive the assets (defaults to caller)
     /// * `memo` - Optional memo for the transaction
+    /// * `min_assets_out` - Abort if fewer assets would be paid out
     fn redeem(
         &mut self,
         shares: U128,
         receiver_id: Option<AccountId>,
         memo: Option<String>,
+        min_assets_out: Option<U128>,
     ) -> PromiseOrValue<U128>;
 
     /// Withdraws a specific amount of underlying assets.
@@ -60,11 +68,13 @@ pub trait VaultCore: FungibleTokenCore + FungibleTokenReceiver {
     /// * `assets` - Amount of assets to withdraw
     /// * `receiver_id` - Account to receive the assets (defaults to caller)
     /// * `memo` - Optional memo for the transaction
+    /// * `min_assets_out` - Abort if fewer assets would be paid out
     fn withdraw(
         &mut self,
         assets: U128,
         receiver_id: Option<AccountId>,
         memo: Option<String>,
+        min_assets_out: Option<U128>,
     ) -> PromiseOrValue<U128>;
 
     /// Converts an asset amount to equivalent shares.
@@ -7,10 +7,26 @@
 //!
 //! - `VaultDeposit`: Emitted when assets are deposited into the vault
 //! - `VaultWithdraw`: Emitted when assets are withdrawn from the vault
+//! - `VaultDonation`: Emitted when assets are donated without minting shares
+//! - `VaultRedeemToChain`: Emitted when shares are redeemed straight into a bridge withdrawal
+//! - `DustSwept`: Emitted when a dust share position is burned
+//! - `IntentCreated`: Emitted when a solver's borrow lands and the intent is recorded
+//! - `BorrowFailed`: Emitted when a borrow transfer fails and is rolled back
+//! - `IntentRepaid`: Emitted when a solver repays a borrowed intent
+//! - `IntentStateChanged`: Emitted when a solver advances an intent's state
+//! - `IntentsCleared`: Emitted when the owner wipes all intents
+//! - `AssetChanged`: Emitted when the owner migrates the vault to a new asset
+//! - `ProtocolFeesClaimed`: Emitted when accrued protocol fees reach the treasury
+//! - `TokensRecovered`: Emitted when the owner recovers stray non-asset tokens
+//! - `MetadataUpdated`: Emitted when the owner replaces the share token metadata
+//! - `LossRealized`: Emitted when the owner writes off a defaulted intent
+//! - `ShareApproval`: Emitted when a share allowance is set or spent
 //!
 //! ## Format
 //!
-//! Events follow the NEP-000 standard:
+//! Events follow the NEP-297 layout. The `standard` and `version` fields
+//! come from the contract's `event_standard` and `event_version`, which
+//! default to `nep000` and `1.0.0`:
 //! ```json
 //! {
 //!   "standard": "nep000",
@@ -20,7 +36,8 @@
 //! }
 //! ```
 
-use near_sdk::json_types::U128;
+use crate::intents::State;
+use near_sdk::json_types::{U128, U64};
 use near_sdk::serde::Serialize;
 use near_sdk::{env, AccountIdRef};
 
@@ -28,16 +45,37 @@ use near_sdk::{env, AccountIdRef};
 // Event Wrapper
 // ============================================================================
 
-/// Top-level event wrapper for NEP-000 compliance.
+/// Default `standard` field of emitted events.
+pub const DEFAULT_EVENT_STANDARD: &str = "nep000";
+
+/// Default `version` field of emitted events.
+pub const DEFAULT_EVENT_VERSION: &str = "1.0.0";
+
+/// The `standard` and `version` stamped on every emitted event.
+///
+/// Forks sharing an indexer set their own values to keep their events
+/// apart; see `Contract::set_event_standard`.
+#[derive(Debug, Clone, Copy)]
+pub struct EventStandard<'a> {
+    /// Event standard name.
+    pub name: &'a str,
+    /// Event standard version.
+    pub version: &'a str,
+}
+
+/// Top-level NEP-297 event wrapper.
 #[derive(Serialize, Debug)]
 #[serde(crate = "near_sdk::serde")]
-#[serde(tag = "standard")]
 #[must_use = "don't forget to `.emit()` this event"]
-#[serde(rename_all = "snake_case")]
 #[allow(unused)]
-pub(crate) enum NearEvent<'a> {
-    /// NEP-000 standard event container.
-    Nep000(Nep000Event<'a>),
+pub(crate) struct NearEvent<'a> {
+    /// Event standard name.
+    standard: &'a str,
+    /// Event standard version.
+    version: &'a str,
+    /// The actual event data.
+    #[serde(flatten)]
+    event_kind: Nep000EventKind<'a>,
 }
 
 #[allow(unused)]
@@ -90,13 +128,13 @@ pub struct VaultDeposit<'a> {
 #[allow(unused)]
 impl VaultDeposit<'_> {
     /// Emits a single deposit event.
-    pub fn emit(self) {
-        Self::emit_many(&[self])
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
     }
 
     /// Emits multiple deposit events in a single log.
-    pub fn emit_many(data: &[VaultDeposit<'_>]) {
-        new_000_v1(Nep000EventKind::VaultDeposit(data)).emit()
+    pub fn emit_many(data: &[VaultDeposit<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::VaultDeposit(data)).emit()
     }
 }
 
@@ -127,31 +165,570 @@ pub struct VaultWithdraw<'a> {
 #[allow(unused)]
 impl VaultWithdraw<'_> {
     /// Emits a single withdraw event.
-    pub fn emit(self) {
-        Self::emit_many(&[self])
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
     }
 
     /// Emits multiple withdraw events in a single log.
-    pub fn emit_many(data: &[VaultWithdraw<'_>]) {
-        new_000_v1(Nep000EventKind::VaultWithdraw(data)).emit()
+    pub fn emit_many(data: &[VaultWithdraw<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::VaultWithdraw(data)).emit()
     }
 }
 
 // ============================================================================
-// Internal Event Structures
+// Vault Donation Event
 // ============================================================================
 
-/// NEP-000 event payload structure.
-#[derive(Serialize, Debug)]
+/// Event data for vault donations.
+///
+/// Emitted when assets are added to the vault without minting shares,
+/// raising the share price for every existing holder.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
-pub(crate) struct Nep000Event<'a> {
-    /// Event format version.
-    version: &'static str,
-    /// The actual event data.
-    #[serde(flatten)]
-    event_kind: Nep000EventKind<'a>,
+pub struct VaultDonation<'a> {
+    /// The account that donated the assets.
+    pub sender_id: &'a AccountIdRef,
+    /// The amount of assets donated.
+    pub assets: U128,
+    /// Optional memo for the donation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+#[allow(unused)]
+impl VaultDonation<'_> {
+    /// Emits a single donation event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple donation events in a single log.
+    pub fn emit_many(data: &[VaultDonation<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::VaultDonation(data)).emit()
+    }
+}
+
+// ============================================================================
+// Dust Swept Event
+// ============================================================================
+
+/// Event data for swept dust positions.
+///
+/// Emitted when shares worth less than the minimum redemption are burned.
+/// Their asset value stays in the vault and accrues to remaining holders.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DustSwept<'a> {
+    /// The account whose shares were burned.
+    pub account_id: &'a AccountIdRef,
+    /// The amount of shares burned.
+    pub shares: U128,
+    /// The asset value of the burned shares, left in the vault.
+    pub assets: U128,
+    /// Whether the account's share registration was removed.
+    pub unregistered: bool,
+}
+
+#[allow(unused)]
+impl DustSwept<'_> {
+    /// Emits a single dust sweep event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple dust sweep events in a single log.
+    pub fn emit_many(data: &[DustSwept<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::DustSwept(data)).emit()
+    }
+}
+
+// ============================================================================
+// Intent Created Event
+// ============================================================================
+
+/// Event data for newly recorded intents.
+///
+/// Emitted once the borrowed liquidity has reached the solver and the intent
+/// is stored. Vault totals are the values right after the borrow.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentCreated<'a> {
+    /// The solver that borrowed.
+    pub solver_id: &'a AccountIdRef,
+    /// The index of the new intent.
+    pub intent_index: U128,
+    /// The principal borrowed.
+    pub borrow_amount: U128,
+    /// Hash of the user deposit the intent fulfils.
+    pub user_deposit_hash: &'a str,
+    /// Block timestamp (nanoseconds) the intent was recorded at.
+    pub created: U64,
+    /// Outstanding principal across all intents, including this one.
+    pub total_borrowed: U128,
+    /// Idle vault assets after the borrow.
+    pub total_assets: U128,
+}
+
+#[allow(unused)]
+impl IntentCreated<'_> {
+    /// Emits a single intent creation event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple intent creation events in a single log.
+    pub fn emit_many(data: &[IntentCreated<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::IntentCreated(data)).emit()
+    }
+}
+
+// ============================================================================
+// Borrow Failed Event
+// ============================================================================
+
+/// Event data for failed borrows.
+///
+/// Emitted when the transfer to the solver fails. No intent is created and
+/// the amount is returned to `total_assets`.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BorrowFailed<'a> {
+    /// The solver whose borrow failed.
+    pub solver_id: &'a AccountIdRef,
+    /// The amount rolled back into the vault.
+    pub amount: U128,
+    /// Hash of the user deposit the borrow was for.
+    pub user_deposit_hash: &'a str,
+}
+
+#[allow(unused)]
+impl BorrowFailed<'_> {
+    /// Emits a single failed borrow event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple failed borrow events in a single log.
+    pub fn emit_many(data: &[BorrowFailed<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::BorrowFailed(data)).emit()
+    }
+}
+
+// ============================================================================
+// Intent Repaid Event
+// ============================================================================
+
+/// Event data for intent repayments.
+///
+/// Emitted when a solver repays an intent. The intent is deleted right
+/// after, so this log is the on-chain record of what was actually repaid.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentRepaid<'a> {
+    /// The solver that repaid the intent.
+    pub solver_id: &'a AccountIdRef,
+    /// The index of the repaid intent.
+    pub intent_index: U128,
+    /// The principal that was borrowed.
+    pub borrow_amount: U128,
+    /// The amount actually repaid (principal + yield).
+    pub repayment_amount: U128,
+}
+
+#[allow(unused)]
+impl IntentRepaid<'_> {
+    /// Emits a single repayment event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple repayment events in a single log.
+    pub fn emit_many(data: &[IntentRepaid<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::IntentRepaid(data)).emit()
+    }
+}
+
+/// Event data for intents cancelled by their solver.
+///
+/// Emitted by a `cancel_repay` transfer. The principal came back without
+/// the solver fee and the intent is deleted, not kept in the history.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentCancelled<'a> {
+    /// The solver that cancelled the intent.
+    pub solver_id: &'a AccountIdRef,
+    /// The index of the cancelled intent.
+    pub intent_index: U128,
+    /// The principal returned to the vault.
+    pub borrow_amount: U128,
+}
+
+#[allow(unused)]
+impl IntentCancelled<'_> {
+    /// Emits a single cancellation event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple cancellation events in a single log.
+    pub fn emit_many(data: &[IntentCancelled<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::IntentCancelled(data)).emit()
+    }
 }
 
+// ============================================================================
+// Intent State Changed Event
+// ============================================================================
+
+/// Event data for intent lifecycle updates.
+///
+/// Emitted for each intent a solver moves to a new state, whether through
+/// `update_intent_state` or a batched `update_intent_states` call.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentStateChanged<'a> {
+    /// The solver that owns the intent.
+    pub solver_id: &'a AccountIdRef,
+    /// The index of the updated intent.
+    pub intent_index: U128,
+    /// The state before the update.
+    pub old_state: &'a State,
+    /// The state after the update.
+    pub new_state: &'a State,
+}
+
+#[allow(unused)]
+impl IntentStateChanged<'_> {
+    /// Emits a single state change event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple state change events in a single log.
+    pub fn emit_many(data: &[IntentStateChanged<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::IntentStateChanged(data)).emit()
+    }
+}
+
+// ============================================================================
+// Intents Cleared Event
+// ============================================================================
+
+/// Event data for wiping all intents.
+///
+/// A non-zero `abandoned_principal` means borrowed liquidity was written off
+/// and will not come back through repayments.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentsCleared {
+    /// Number of intents removed.
+    pub intents_cleared: u32,
+    /// Borrowed principal that was outstanding when the intents were cleared.
+    pub abandoned_principal: U128,
+    /// Whether the pause and outstanding-borrow checks were skipped.
+    pub forced: bool,
+}
+
+#[allow(unused)]
+impl IntentsCleared {
+    /// Emits a single intents cleared event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple intents cleared events in a single log.
+    pub fn emit_many(data: &[IntentsCleared], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::IntentsCleared(data)).emit()
+    }
+}
+
+// ============================================================================
+// Vault Redeem To Chain Event
+// ============================================================================
+
+/// Event data for redemptions paid out through the OMFT bridge.
+///
+/// Emitted when `redeem_to_chain` burns shares and starts the bridge
+/// withdrawal. A `vault_withdraw` event follows once the transfer succeeds.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VaultRedeemToChain<'a> {
+    /// The account that owned the shares.
+    pub owner_id: &'a AccountIdRef,
+    /// The destination chain family (`evm` or `solana`).
+    pub chain: &'a str,
+    /// The destination address on that chain.
+    pub recipient: &'a str,
+    /// The amount of shares burned.
+    pub shares: U128,
+    /// The amount of assets sent to the bridge.
+    pub assets: U128,
+    /// Optional memo supplied by the owner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+#[allow(unused)]
+impl VaultRedeemToChain<'_> {
+    /// Emits a single redeem-to-chain event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple redeem-to-chain events in a single log.
+    pub fn emit_many(data: &[VaultRedeemToChain<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::VaultRedeemToChain(data)).emit()
+    }
+}
+
+// ============================================================================
+// Asset Changed Event
+// ============================================================================
+
+/// Event data for underlying asset migrations.
+///
+/// Emitted when the owner switches the vault to a new asset token. If the
+/// decimals differ, `total_assets` was rescaled by
+/// `10^(new_decimals - old_decimals)`.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AssetChanged<'a> {
+    /// The previous asset token.
+    pub old_asset: &'a AccountIdRef,
+    /// The new asset token.
+    pub new_asset: &'a AccountIdRef,
+    /// Decimals of the previous asset.
+    pub old_decimals: u8,
+    /// Decimals of the new asset.
+    pub new_decimals: u8,
+    /// Vault assets after rescaling to the new asset's units.
+    pub total_assets: U128,
+}
+
+#[allow(unused)]
+impl AssetChanged<'_> {
+    /// Emits a single asset change event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple asset change events in a single log.
+    pub fn emit_many(data: &[AssetChanged<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::AssetChanged(data)).emit()
+    }
+}
+
+// ============================================================================
+// Protocol Fees Claimed Event
+// ============================================================================
+
+/// Event data for protocol fee claims.
+///
+/// Emitted once the fee transfer to the treasury has succeeded; a failed
+/// transfer restores the accrued fees and emits nothing.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProtocolFeesClaimed<'a> {
+    /// The treasury that received the fees.
+    pub treasury_id: &'a AccountIdRef,
+    /// The amount of asset tokens transferred.
+    pub amount: U128,
+}
+
+#[allow(unused)]
+impl ProtocolFeesClaimed<'_> {
+    /// Emits a single protocol fee claim event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple protocol fee claim events in a single log.
+    pub fn emit_many(data: &[ProtocolFeesClaimed<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::ProtocolFeesClaimed(data)).emit()
+    }
+}
+
+// ============================================================================
+// Tokens Recovered Event
+// ============================================================================
+
+/// Event data for stray token recoveries.
+///
+/// Emitted once the transfer of a non-asset token out of the vault has
+/// succeeded.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokensRecovered<'a> {
+    /// The recovered token contract.
+    pub token: &'a AccountIdRef,
+    /// The account that received the tokens.
+    pub recipient: &'a AccountIdRef,
+    /// The amount transferred.
+    pub amount: U128,
+}
+
+#[allow(unused)]
+impl TokensRecovered<'_> {
+    /// Emits a single token recovery event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple token recovery events in a single log.
+    pub fn emit_many(data: &[TokensRecovered<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::TokensRecovered(data)).emit()
+    }
+}
+
+// ============================================================================
+// Metadata Updated Event
+// ============================================================================
+
+/// Event data for share token metadata updates.
+///
+/// Carries the display fields of the new NEP-148 metadata so indexers can
+/// refresh their copy without calling `ft_metadata`. Decimals never change.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataUpdated<'a> {
+    /// The new token name.
+    pub name: &'a str,
+    /// The new token symbol.
+    pub symbol: &'a str,
+    /// The new icon data URL, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<&'a str>,
+    /// The new reference URL, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<&'a str>,
+}
+
+#[allow(unused)]
+impl MetadataUpdated<'_> {
+    /// Emits a single metadata update event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple metadata update events in a single log.
+    pub fn emit_many(data: &[MetadataUpdated<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::MetadataUpdated(data)).emit()
+    }
+}
+
+// ============================================================================
+// Loss Realized Event
+// ============================================================================
+
+/// Event data for a defaulted intent written off by the owner.
+///
+/// Emitted by `write_off_intent`. The principal leaves `total_borrowed`
+/// without returning to `total_assets`, so lenders absorb it through a
+/// lower share price.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LossRealized<'a> {
+    /// The solver that borrowed the intent.
+    pub solver_id: &'a AccountIdRef,
+    /// The written-off intent index.
+    pub intent_index: U128,
+    /// Borrowed principal that will not be returned.
+    pub principal_lost: U128,
+    /// Cumulative principal written off, including this intent.
+    pub total_written_off: U128,
+    /// Whether the intent was still repayable and `force` was required.
+    pub forced: bool,
+}
+
+#[allow(unused)]
+impl LossRealized<'_> {
+    /// Emits a single loss realized event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple loss realized events in a single log.
+    pub fn emit_many(data: &[LossRealized<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::LossRealized(data)).emit()
+    }
+}
+
+/// Emitted by `approve_shares` and, with the remaining allowance, by
+/// `redeem_from`.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ShareApproval<'a> {
+    /// The share holder granting the allowance.
+    pub owner_id: &'a AccountIdRef,
+    /// The account allowed to redeem the owner's shares.
+    pub spender_id: &'a AccountIdRef,
+    /// Shares the spender may still redeem.
+    pub allowance: U128,
+}
+
+#[allow(unused)]
+impl ShareApproval<'_> {
+    /// Emits a single share approval event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple share approval events in a single log.
+    pub fn emit_many(data: &[ShareApproval<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::ShareApproval(data)).emit()
+    }
+}
+
+/// Emitted by `force_process_with_loss` for each queued redemption paid
+/// less than the asset value stored when it was queued.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedemptionProcessedWithLoss<'a> {
+    /// The account whose shares were burned.
+    pub owner_id: &'a AccountIdRef,
+    /// The account that received the assets.
+    pub receiver_id: &'a AccountIdRef,
+    /// Shares burned for the payout.
+    pub shares: U128,
+    /// Asset value stored when the redemption was queued.
+    pub queued_assets: U128,
+    /// Assets actually paid out.
+    pub paid_assets: U128,
+}
+
+#[allow(unused)]
+impl RedemptionProcessedWithLoss<'_> {
+    /// Emits a single loss-adjusted redemption event.
+    pub fn emit(self, standard: EventStandard<'_>) {
+        Self::emit_many(&[self], standard)
+    }
+
+    /// Emits multiple loss-adjusted redemption events in a single log.
+    pub fn emit_many(data: &[RedemptionProcessedWithLoss<'_>], standard: EventStandard<'_>) {
+        new_event(standard, Nep000EventKind::RedemptionProcessedWithLoss(data)).emit()
+    }
+}
+
+// ============================================================================
+// Internal Event Structures
+// ============================================================================
+
 /// Enum of supported vault event types.
 #[derive(Serialize, Debug)]
 #[serde(crate = "near_sdk::serde")]
@@ -163,17 +740,173 @@ enum Nep000EventKind<'a> {
     VaultDeposit(&'a [VaultDeposit<'a>]),
     /// One or more withdraw events.
     VaultWithdraw(&'a [VaultWithdraw<'a>]),
+    /// One or more donation events.
+    VaultDonation(&'a [VaultDonation<'a>]),
+    /// One or more redeem-to-chain events.
+    VaultRedeemToChain(&'a [VaultRedeemToChain<'a>]),
+    /// One or more dust sweep events.
+    DustSwept(&'a [DustSwept<'a>]),
+    /// One or more intent creation events.
+    IntentCreated(&'a [IntentCreated<'a>]),
+    /// One or more failed borrow events.
+    BorrowFailed(&'a [BorrowFailed<'a>]),
+    /// One or more intent repayment events.
+    IntentRepaid(&'a [IntentRepaid<'a>]),
+    /// One or more intent cancellation events.
+    IntentCancelled(&'a [IntentCancelled<'a>]),
+    /// One or more intent state change events.
+    IntentStateChanged(&'a [IntentStateChanged<'a>]),
+    /// One or more intents cleared events.
+    IntentsCleared(&'a [IntentsCleared]),
+    /// One or more asset change events.
+    AssetChanged(&'a [AssetChanged<'a>]),
+    /// One or more protocol fee claim events.
+    ProtocolFeesClaimed(&'a [ProtocolFeesClaimed<'a>]),
+    /// One or more token recovery events.
+    TokensRecovered(&'a [TokensRecovered<'a>]),
+    /// One or more metadata update events.
+    MetadataUpdated(&'a [MetadataUpdated<'a>]),
+    /// One or more loss realization events.
+    LossRealized(&'a [LossRealized<'a>]),
+    /// One or more share approval events.
+    ShareApproval(&'a [ShareApproval<'a>]),
+    /// One or more loss-adjusted redemption events.
+    RedemptionProcessedWithLoss(&'a [RedemptionProcessedWithLoss<'a>]),
 }
 
-/// Creates a NEP-000 event with the specified version.
-fn new_000<'a>(version: &'static str, event_kind: Nep000EventKind<'a>) -> NearEvent<'a> {
-    NearEvent::Nep000(Nep000Event {
-        version,
+/// Creates an event stamped with `standard`.
+fn new_event<'a>(standard: EventStandard<'a>, event_kind: Nep000EventKind<'a>) -> NearEvent<'a> {
+    NearEvent {
+        standard: standard.name,
+        version: standard.version,
         event_kind,
-    })
+    }
 }
 
-/// Creates a NEP-000 v1.0.0 event.
-fn new_000_v1(event_kind: Nep000EventKind) -> NearEvent {
-    new_000("1.0.0", event_kind)
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::serde_json::{self, Value};
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const DEFAULT_STANDARD: EventStandard<'static> = EventStandard {
+        name: DEFAULT_EVENT_STANDARD,
+        version: DEFAULT_EVENT_VERSION,
+    };
+
+    /// Returns the only log line, parsed from behind its `EVENT_JSON:` prefix.
+    fn single_event() -> Value {
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1, "expected exactly one log, got {:?}", logs);
+        let json = logs[0]
+            .strip_prefix("EVENT_JSON:")
+            .expect("log is missing the EVENT_JSON prefix");
+        serde_json::from_str(json).expect("event is not valid JSON")
+    }
+
+    /// Asserts the NEP-000 envelope and returns the single data entry.
+    fn event_data(event: &Value, name: &str) -> Value {
+        assert_eq!(event["standard"], "nep000");
+        assert_eq!(event["version"], "1.0.0");
+        assert_eq!(event["event"], name);
+        let data = event["data"].as_array().expect("data is not an array");
+        assert_eq!(data.len(), 1);
+        data[0].clone()
+    }
+
+    #[test]
+    fn vault_deposit_schema() {
+        testing_env!(VMContextBuilder::new().build());
+        VaultDeposit {
+            sender_id: AccountIdRef::new_or_panic("alice.test"),
+            owner_id: AccountIdRef::new_or_panic("bob.test"),
+            assets: U128(1_000_000),
+            shares: U128(1_000_000_000),
+            memo: Some("Deposit"),
+        }
+        .emit(DEFAULT_STANDARD);
+
+        let data = event_data(&single_event(), "vault_deposit");
+        assert_eq!(data["sender_id"], "alice.test");
+        assert_eq!(data["owner_id"], "bob.test");
+        // U128 amounts are serialized as decimal strings
+        assert_eq!(data["assets"], Value::String("1000000".to_string()));
+        assert_eq!(data["shares"], Value::String("1000000000".to_string()));
+        assert_eq!(data["memo"], "Deposit");
+        assert_eq!(data.as_object().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn vault_withdraw_schema_omits_missing_memo() {
+        testing_env!(VMContextBuilder::new().build());
+        VaultWithdraw {
+            owner_id: AccountIdRef::new_or_panic("alice.test"),
+            receiver_id: AccountIdRef::new_or_panic("carol.test"),
+            shares: U128(500_000_000),
+            assets: U128(500_000),
+            memo: None,
+        }
+        .emit(DEFAULT_STANDARD);
+
+        let data = event_data(&single_event(), "vault_withdraw");
+        assert_eq!(data["owner_id"], "alice.test");
+        assert_eq!(data["receiver_id"], "carol.test");
+        assert_eq!(data["shares"], Value::String("500000000".to_string()));
+        assert_eq!(data["assets"], Value::String("500000".to_string()));
+        assert!(data.get("memo").is_none(), "memo: None must be omitted");
+        assert_eq!(data.as_object().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn emit_many_batches_entries_in_one_log() {
+        testing_env!(VMContextBuilder::new().build());
+        let deposits = [
+            VaultDeposit {
+                sender_id: AccountIdRef::new_or_panic("alice.test"),
+                owner_id: AccountIdRef::new_or_panic("alice.test"),
+                assets: U128(1),
+                shares: U128(1_000),
+                memo: None,
+            },
+            VaultDeposit {
+                sender_id: AccountIdRef::new_or_panic("bob.test"),
+                owner_id: AccountIdRef::new_or_panic("bob.test"),
+                assets: U128(2),
+                shares: U128(2_000),
+                memo: None,
+            },
+        ];
+        VaultDeposit::emit_many(&deposits, DEFAULT_STANDARD);
+
+        let event = single_event();
+        assert_eq!(event["event"], "vault_deposit");
+        let data = event["data"].as_array().unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[1]["sender_id"], "bob.test");
+        assert_eq!(data[1]["shares"], "2000");
+    }
+
+    #[test]
+    fn custom_standard_replaces_envelope_fields() {
+        testing_env!(VMContextBuilder::new().build());
+        IntentsCleared {
+            intents_cleared: 2,
+            abandoned_principal: U128(0),
+            forced: false,
+        }
+        .emit(EventStandard {
+            name: "fork-vault",
+            version: "0.1.0",
+        });
+
+        let event = single_event();
+        assert_eq!(event["standard"], "fork-vault");
+        assert_eq!(event["version"], "0.1.0");
+        assert_eq!(event["event"], "intents_cleared");
+    }
 }
@@ -15,13 +15,10 @@
 use near_contract_standards::fungible_token::{
     core::ext_ft_core, events::FtBurn, FungibleTokenCore,
 };
-use near_sdk::{env, ext_contract, json_types::U128, AccountId, Gas, NearToken, Promise};
+use near_sdk::{env, ext_contract, json_types::U128, AccountId, NearToken, Promise};
 
 use super::mul_div::{mul_div, Rounding};
 
-/// Gas allocation for asset transfer during withdrawal.
-pub const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(30);
-
 use crate::Contract;
 
 // ============================================================================
@@ -77,11 +74,11 @@ impl Contract {
         // =====================================================================
         ext_ft_core::ext(self.asset.clone())
             .with_attached_deposit(NearToken::from_yoctonear(1))
-            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .with_static_gas(self.gas_config.withdraw_transfer)
             .ft_transfer(receiver_id.clone(), U128(amount), memo.clone())
             .then(
                 ext_self::ext(env::current_account_id())
-                    .with_static_gas(Gas::from_tgas(10))
+                    .with_static_gas(self.gas_config.withdraw_callback)
                     .resolve_withdraw(owner, receiver_id, U128(shares), U128(amount), memo),
             )
     }
@@ -132,6 +129,7 @@ impl Contract {
             .total_assets
             .checked_sub(assets_to_transfer)
             .expect("total_assets underflow");
+        self.last_redemption_block = env::block_height();
 
         FtBurn {
             owner_id: &owner,
@@ -188,6 +186,9 @@ impl Contract {
     ///
     /// Formula: shares = (assets * total_supply) / (total_assets + borrowed + yield)
     ///
+    /// Rounds with the owner-set `deposit_rounding` policy (`Down` unless
+    /// changed). The first deposit is an exact multiplication and needs none.
+    ///
     /// # Arguments
     ///
     /// * `assets` - The asset amount being deposited
@@ -200,7 +201,14 @@ impl Contract {
 
         // First deposit: use 1:1 ratio with extra decimals
         if total_supply == 0 {
-            return assets * 10u128.pow(self.extra_decimals as u32);
+            return assets
+                .checked_mul(10u128.pow(self.extra_decimals as u32))
+                .unwrap_or_else(|| {
+                    env::panic_str(&format!(
+                        "First deposit of {} assets overflows share supply at {} extra decimals",
+                        assets, self.extra_decimals
+                    ))
+                });
         }
 
         // Include expected yield in denominator to protect existing lenders
@@ -214,9 +222,35 @@ impl Contract {
             .expect("denominator overflow")
             .max(1);
 
-        let result = mul_div(assets, total_supply, denominator, Rounding::Down);
+        mul_div(assets, total_supply, denominator, self.deposit_rounding)
+    }
 
-        result
+    /// Converts assets to shares against realized assets only.
+    ///
+    /// The worst-case counterpart of `internal_convert_to_shares_deposit`:
+    /// outstanding borrows and their expected yield are left out of the
+    /// denominator, as if every open intent defaulted. Donations are already
+    /// part of `total_assets`. The result is never below the deposit preview.
+    ///
+    /// Formula: shares = (assets * total_supply) / total_assets
+    ///
+    /// # Arguments
+    ///
+    /// * `assets` - The asset amount being deposited
+    ///
+    /// # Returns
+    ///
+    /// The share count priced at the realized share price.
+    pub fn internal_convert_to_shares_conservative(&self, assets: u128) -> u128 {
+        if self.token.ft_total_supply().0 == 0 {
+            return self.internal_convert_to_shares_deposit(assets);
+        }
+        mul_div(
+            assets,
+            self.token.ft_total_supply().0,
+            self.total_assets.max(1),
+            self.deposit_rounding,
+        )
     }
 
     /// Converts shares to equivalent assets.
@@ -242,16 +276,20 @@ impl Contract {
         }
 
         let (total_borrowed, expected_yield) = self.calculate_expected_yield();
-        let total_assets = self.total_assets + total_borrowed + expected_yield;
+        let total_assets = self
+            .total_assets
+            .checked_add(total_borrowed)
+            .and_then(|value| value.checked_add(expected_yield))
+            .expect("total value overflow");
 
-        env::log_str(&format!(
+        self.log_debug(&format!(
             "internal_convert_to_assets: shares={} total_supply={} total_assets={} total_borrowed={} expected_yield={} calculated_total={}",
             shares, total_supply, self.total_assets, total_borrowed, expected_yield, total_assets
         ));
 
         let result = mul_div(shares, total_assets, total_supply, rounding);
 
-        env::log_str(&format!(
+        self.log_debug(&format!(
             "internal_convert_to_assets: result={} (shares={} * total_assets={} / total_supply={})",
             result, shares, total_assets, total_supply
         ));
@@ -261,14 +299,44 @@ impl Contract {
 
     /// Calculates expected yield from all active (unpaid) borrows.
     ///
-    /// Uses the tracked `total_borrowed` field for O(1) lookup instead of
-    /// iterating through all intents.
+    /// Uses the tracked `total_borrowed`, `pending_borrowed` and
+    /// `open_intent_fees` fields for O(1) lookup instead of iterating
+    /// through all intents. Each open intent earns the solver fee it was
+    /// borrowed at. A borrow whose transfer is still in flight already left
+    /// `total_assets`, so it counts as outstanding right away, at the
+    /// current `solver_fee`. Intents carry no expiry yet, so every open
+    /// borrow counts towards the expected yield. Only the lenders' part is
+    /// counted; the protocol fee is excluded.
+    ///
+    /// See [`Self::expected_yield_for`] for how the yield saturates.
     ///
     /// # Returns
     ///
-    /// A tuple of (total_borrowed, expected_yield).
+    /// A tuple of (outstanding borrowed principal, expected_yield).
     pub fn calculate_expected_yield(&self) -> (u128, u128) {
-        let expected_yield = self.total_borrowed * self.solver_fee as u128 / 100;
-        (self.total_borrowed, expected_yield)
+        let outstanding = self.total_borrowed.saturating_add(self.pending_borrowed);
+        (
+            outstanding,
+            self.expected_yield_for(outstanding, self.open_intent_fees),
+        )
+    }
+
+    /// Lenders' expected yield on `outstanding` principal whose recorded
+    /// intents owe `open_intent_fees`, plus the in-flight borrow's fee.
+    ///
+    /// The yield is computed with `mul_div` (no intermediate overflow) and
+    /// saturates: it is capped so that
+    /// `total_assets + outstanding + expected_yield` never exceeds
+    /// `u128::MAX`, dropping to 0 if the first two terms already fill it.
+    pub(crate) fn expected_yield_for(&self, outstanding: u128, open_intent_fees: u128) -> u128 {
+        let gross_yield = open_intent_fees.saturating_add(Self::internal_solver_fee(
+            self.pending_borrowed,
+            self.solver_fee,
+        ));
+        let expected_yield = gross_yield - self.internal_protocol_fee(gross_yield);
+        let headroom = u128::MAX
+            .saturating_sub(self.total_assets)
+            .saturating_sub(outstanding);
+        expected_yield.min(headroom)
     }
 }
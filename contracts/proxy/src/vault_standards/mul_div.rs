@@ -13,8 +13,12 @@
 //! - Use `Down` when calculating shares to mint (favor vault)
 //! - Use `Up` when calculating shares to burn (favor vault)
 
+use near_sdk::near;
+
 /// Rounding direction for division operations.
-#[derive(Clone, Copy, Debug)]
+#[near(serializers = [json, borsh])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Rounding {
     /// Round towards zero (floor division).
     Down,
@@ -64,3 +68,84 @@ pub fn mul_div(x: u128, y: u128, denominator: u128, rounding: Rounding) -> u128
         }
     }
 }
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::U256;
+    use super::*;
+
+    #[test]
+    fn exact_division_ignores_rounding() {
+        assert_eq!(mul_div(6, 4, 3, Rounding::Down), 8);
+        assert_eq!(mul_div(6, 4, 3, Rounding::Up), 8);
+    }
+
+    #[test]
+    fn remainder_rounds_by_direction() {
+        // 7 * 3 / 4 = 5.25
+        assert_eq!(mul_div(7, 3, 4, Rounding::Down), 5);
+        assert_eq!(mul_div(7, 3, 4, Rounding::Up), 6);
+    }
+
+    #[test]
+    fn zero_numerator_is_zero() {
+        assert_eq!(mul_div(0, 1_000, 7, Rounding::Down), 0);
+        assert_eq!(mul_div(1_000, 0, 7, Rounding::Up), 0);
+    }
+
+    #[test]
+    fn unit_denominator_is_plain_product() {
+        assert_eq!(mul_div(123_456, 789, 1, Rounding::Down), 123_456 * 789);
+        assert_eq!(mul_div(123_456, 789, 1, Rounding::Up), 123_456 * 789);
+    }
+
+    #[test]
+    fn wide_intermediate_does_not_overflow() {
+        let max = u128::MAX;
+        assert_eq!(mul_div(max, max, max, Rounding::Down), max);
+        assert_eq!(mul_div(max, max, max, Rounding::Up), max);
+        // MAX * 2 / 3 needs 129 bits before dividing
+        assert_eq!(mul_div(max, 2, 3, Rounding::Down), max / 3 * 2);
+        assert_eq!(mul_div(max, 2, 3, Rounding::Up), max / 3 * 2);
+        assert_eq!(mul_div(max - 1, max, max, Rounding::Down), max - 1);
+        assert_eq!(mul_div(max, max - 1, max - 1, Rounding::Up), max);
+    }
+
+    /// Deterministic xorshift generator so failures reproduce.
+    fn next(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    #[test]
+    fn rounding_properties_hold_for_random_inputs() {
+        let mut state = 0x9E37_79B9_7F4A_7C15;
+        for _ in 0..10_000 {
+            let x = (next(&mut state) as u128) << 32 | next(&mut state) as u128;
+            let y = next(&mut state) as u128;
+            // Keep the quotient within u128
+            let denominator = (next(&mut state) as u128).max(1);
+
+            let down = mul_div(x, y, denominator, Rounding::Down);
+            let up = mul_div(x, y, denominator, Rounding::Up);
+            let product = U256::from(x) * U256::from(y);
+
+            assert!(down <= up, "down > up for {} * {} / {}", x, y, denominator);
+            assert!(
+                up - down <= 1,
+                "gap > 1 for {} * {} / {}",
+                x,
+                y,
+                denominator
+            );
+            assert!(U256::from(down) * U256::from(denominator) <= product);
+            assert!(U256::from(up) * U256::from(denominator) >= product);
+        }
+    }
+}
@@ -13,6 +13,15 @@
 //! The OMFT bridge recognizes a special memo format `WITHDRAW_TO:<address>` when
 //! the receiver of an `ft_transfer` is the token contract itself. This triggers
 //! the bridge to burn the tokens on NEAR and mint them on the destination chain.
+//!
+//! Lenders reach the same path through `redeem_to_chain` in the vault module,
+//! which burns shares and bridges the redeemed assets in one call.
+//!
+//! ## Per-Chain Limits
+//!
+//! The owner can set a `ChainConfig` per chain family. Withdrawals below its
+//! `min_withdraw` are rejected, and its `bridge_fee` is kept back from the
+//! bridged amount to cover the cost of the bridge transaction.
 
 use crate::*;
 use near_contract_standards::fungible_token::core::ext_ft_core;
@@ -21,8 +30,145 @@ use near_sdk::{json_types::U128, Gas};
 /// Gas allocation for OMFT withdrawal cross-contract call.
 const GAS_FOR_OMFT_WITHDRAW: Gas = Gas::from_tgas(30);
 
+/// Destination chain family for an OMFT bridge withdrawal.
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BridgeChain {
+    /// Any EVM chain; the recipient is a 0x-prefixed hex address.
+    Evm,
+    /// Solana; the recipient is a Base58 address.
+    Solana,
+}
+
+impl BridgeChain {
+    /// Returns the chain name as used in events.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BridgeChain::Evm => "evm",
+            BridgeChain::Solana => "solana",
+        }
+    }
+
+    /// Validates `recipient` for this chain and builds the bridge memo.
+    ///
+    /// # Returns
+    ///
+    /// The `WITHDRAW_TO:<address>` memo with surrounding whitespace trimmed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address is malformed for this chain.
+    pub fn bridge_memo(&self, recipient: &str) -> String {
+        let address = match self {
+            BridgeChain::Evm => validate_evm_address(recipient),
+            BridgeChain::Solana => validate_solana_address(recipient),
+        };
+        format!("WITHDRAW_TO:{}", address)
+    }
+}
+
+/// Withdrawal limits for one destination chain, set with `set_chain_config`.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainConfig {
+    /// Smallest amount, before the fee, that may be bridged to this chain.
+    pub min_withdraw: U128,
+    /// Amount kept back from every withdrawal to this chain.
+    pub bridge_fee: U128,
+}
+
+/// Validates an EVM address (0x + 40 hex characters) and returns it trimmed.
+fn validate_evm_address(evm_address: &str) -> &str {
+    let evm = evm_address.trim();
+    require!(
+        evm.starts_with("0x")
+            && evm.len() == 42
+            && evm.chars().skip(2).all(|c| c.is_ascii_hexdigit()),
+        "invalid EVM address format"
+    );
+    evm
+}
+
+/// Validates a Solana address (Base58, 32-64 chars) and returns it trimmed.
+fn validate_solana_address(sol_address: &str) -> &str {
+    let sol = sol_address.trim();
+    require!(
+        sol.len() >= 32 && sol.len() <= 64,
+        "invalid Solana address length"
+    );
+
+    // Validate Base58 character set (excludes 0, O, I, l)
+    let is_base58 = sol.chars().all(|c| {
+        matches!(c,
+            '1'..='9'
+            | 'A'..='H' | 'J'..='N' | 'P'..='Z'
+            | 'a'..='k' | 'm'..='z'
+        )
+    });
+    require!(is_base58, "invalid Solana address characters");
+    sol
+}
+
+impl Contract {
+    /// Applies `chain`'s configured limits to a withdrawal of `amount`.
+    ///
+    /// # Returns
+    ///
+    /// The amount to bridge after the chain's bridge fee, or `amount`
+    /// unchanged if the chain has no config.
+    ///
+    /// # Panics
+    ///
+    /// Panics with `E_BELOW_CHAIN_MINIMUM` if `amount` is below the chain's
+    /// minimum or does not exceed its bridge fee.
+    fn internal_net_bridge_amount(&self, chain: BridgeChain, amount: u128) -> u128 {
+        let Some(config) = self.chain_config.get(chain.as_str()) else {
+            return amount;
+        };
+        let minimum = config.min_withdraw.0.max(config.bridge_fee.0 + 1);
+        if amount < minimum {
+            VaultError::BelowChainMinimum {
+                chain: chain.as_str().to_string(),
+                amount,
+                minimum,
+            }
+            .panic();
+        }
+        amount - config.bridge_fee.0
+    }
+}
+
 #[near]
 impl Contract {
+    /// Sets or clears the withdrawal limits for a destination chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - Destination chain family
+    /// * `config` - New limits, or `None` to remove them
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the contract owner.
+    pub fn set_chain_config(&mut self, chain: BridgeChain, config: Option<ChainConfig>) {
+        self.require_owner();
+        let key = chain.as_str().to_string();
+        match config {
+            Some(config) => {
+                self.chain_config.insert(key, config);
+            }
+            None => {
+                self.chain_config.remove(&key);
+            }
+        }
+    }
+
+    /// Returns the withdrawal limits for `chain`, if any are set.
+    pub fn get_chain_config(&self, chain: BridgeChain) -> Option<ChainConfig> {
+        self.chain_config.get(chain.as_str()).cloned()
+    }
+
     /// Burns OMFT tokens on NEAR and withdraws them to an EVM address.
     ///
     /// This initiates a cross-chain transfer by calling `ft_transfer` on the
@@ -41,6 +187,8 @@ impl Contract {
     /// - Token contract must match the vault's underlying asset
     /// - Amount must not exceed available vault assets
     /// - EVM address must be valid format (0x + 40 hex characters)
+    /// - Amount must meet the EVM `ChainConfig` minimum, if set; its bridge
+    ///   fee is deducted before bridging
     ///
     /// # Returns
     ///
@@ -63,7 +211,7 @@ impl Contract {
         evm_address: String,
     ) -> Promise {
         // Access control
-        self.require_not_paused();
+        self.require_not_paused_for(PauseFlag::Withdrawals);
         self.require_owner();
         near_sdk::assert_one_yocto();
 
@@ -78,17 +226,9 @@ impl Contract {
             "amount exceeds available assets"
         );
 
-        // Validate EVM address format (0x + 40 hex characters)
-        let evm = evm_address.trim().to_string();
-        require!(
-            evm.starts_with("0x")
-                && evm.len() == 42
-                && evm.chars().skip(2).all(|c| c.is_ascii_hexdigit()),
-            "invalid EVM address format"
-        );
-
-        // Construct the bridge memo
-        let memo = format!("WITHDRAW_TO:{}", evm);
+        // Validate EVM address format and construct the bridge memo
+        let memo = BridgeChain::Evm.bridge_memo(&evm_address);
+        let amount = U128(self.internal_net_bridge_amount(BridgeChain::Evm, amount.0));
 
         // =====================================================================
         // Cross-Contract Call: OMFT Bridge Withdrawal
@@ -121,6 +261,8 @@ impl Contract {
     /// - Token contract must match the vault's underlying asset
     /// - Amount must not exceed available vault assets
     /// - Solana address must be valid Base58 (32-44 characters, no 0/O/I/l)
+    /// - Amount must meet the Solana `ChainConfig` minimum, if set; its
+    ///   bridge fee is deducted before bridging
     ///
     /// # Returns
     ///
@@ -133,7 +275,7 @@ impl Contract {
         sol_address: String,
     ) -> Promise {
         // Access control
-        self.require_not_paused();
+        self.require_not_paused_for(PauseFlag::Withdrawals);
         self.require_owner();
         near_sdk::assert_one_yocto();
 
@@ -148,25 +290,9 @@ impl Contract {
             "amount exceeds available assets"
         );
 
-        // Validate Solana address format (Base58, 32-44 chars)
-        let sol = sol_address.trim().to_string();
-        require!(
-            sol.len() >= 32 && sol.len() <= 64,
-            "invalid Solana address length"
-        );
-
-        // Validate Base58 character set (excludes 0, O, I, l)
-        let is_base58 = sol.chars().all(|c| {
-            matches!(c,
-                '1'..='9'
-                | 'A'..='H' | 'J'..='N' | 'P'..='Z'
-                | 'a'..='k' | 'm'..='z'
-            )
-        });
-        require!(is_base58, "invalid Solana address characters");
-
-        // Construct the bridge memo
-        let memo = format!("WITHDRAW_TO:{}", sol);
+        // Validate Solana address format and construct the bridge memo
+        let memo = BridgeChain::Solana.bridge_memo(&sol_address);
+        let amount = U128(self.internal_net_bridge_amount(BridgeChain::Solana, amount.0));
 
         // =====================================================================
         // Cross-Contract Call: OMFT Bridge Withdrawal to Solana
@@ -363,4 +489,77 @@ mod tests {
         );
         assert_eq!(contract.total_assets, before);
     }
+
+    /// Owner context with 20 USDC idle and an EVM minimum of 5 USDC with a
+    /// 0.5 USDC bridge fee.
+    fn contract_with_evm_config() -> Contract {
+        let mut contract = ContractBuilder::new("owner.test", "usdc.test")
+            .total_assets(20_000_000)
+            .predecessor("owner.test")
+            .attached(1)
+            .build();
+        contract.set_chain_config(
+            BridgeChain::Evm,
+            Some(ChainConfig {
+                min_withdraw: U128(5_000_000),
+                bridge_fee: U128(500_000),
+            }),
+        );
+        contract
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "E_BELOW_CHAIN_MINIMUM: Withdrawal of 4999999 to evm is below the chain minimum 5000000"
+    )]
+    fn evm_withdraw_below_chain_minimum_is_rejected() {
+        let mut contract = contract_with_evm_config();
+        let _ = contract.withdraw_omft_to_evm(
+            "usdc.test".parse().unwrap(),
+            U128(4_999_999),
+            "0x1111111111111111111111111111111111111111".to_string(),
+        );
+    }
+
+    #[test]
+    fn evm_withdraw_deducts_bridge_fee() {
+        let mut contract = contract_with_evm_config();
+        assert_eq!(
+            contract
+                .get_chain_config(BridgeChain::Evm)
+                .unwrap()
+                .bridge_fee,
+            U128(500_000)
+        );
+        assert!(contract.get_chain_config(BridgeChain::Solana).is_none());
+
+        let _ = contract.withdraw_omft_to_evm(
+            "usdc.test".parse().unwrap(),
+            U128(5_000_000),
+            "0x1111111111111111111111111111111111111111".to_string(),
+        );
+
+        let args = near_sdk::test_utils::get_created_receipts()
+            .into_iter()
+            .flat_map(|receipt| receipt.actions)
+            .find_map(|action| match action {
+                near_sdk::mock::MockAction::FunctionCallWeight {
+                    method_name, args, ..
+                } if method_name == b"ft_transfer" => Some(args),
+                _ => None,
+            })
+            .expect("bridge ft_transfer");
+        let args: near_sdk::serde_json::Value = near_sdk::serde_json::from_slice(&args).unwrap();
+        assert_eq!(args["amount"], "4500000");
+    }
+
+    #[test]
+    fn unconfigured_chain_has_no_minimum() {
+        let mut contract = contract_with_evm_config();
+        let _ = contract.withdraw_omft_to_solana(
+            "usdc.test".parse().unwrap(),
+            U128(1),
+            "1111111111111111111111111111111111111111111111111111111111111111".to_string(),
+        );
+    }
 }
@@ -9,8 +9,9 @@ set -e  # Exit on error
 # Running `cargo test --test <file>` executes ALL test functions within that file
 #
 # Files with multiple tests:
-#   - sandbox_test.rs (5 tests): test_mock_ft_deployment_only, test_contract_deployment,
-#                                test_approve_codehash, test_vault_initialization, test_vault_conversion_functions
+#   - sandbox_test.rs (6 tests): test_mock_ft_deployment_only, test_contract_deployment,
+#                                test_approve_codehash, test_vault_initialization,
+#                                test_min_deposit_from_asset_metadata, test_vault_conversion_functions
 #   - test_partial_repayment.rs (4 tests): test_partial_repayment_less_than_principal,
 #                                          test_repayment_exact_principal_no_yield, test_repayment_with_yield,
 #                                          test_repayment_with_extra_yield
@@ -20,26 +21,44 @@ set -e  # Exit on error
 #   - test_withdrawals.rs (2 tests): test_withdrawals, test_withdraw_omft_to_solana_enqueues_transfer
 #
 TESTS=(
-    # Basic sandbox tests (5 tests in file)
+    # Basic sandbox tests (6 tests in file)
     "sandbox_test"
     # Vault operations
     "test_vault_deposit"
     "test_withdrawals"
+    "test_redeem_to_chain"
+    "test_wrong_token_refund"
+    "test_recover_tokens"
     # Solver borrowing
     "test_solver_borrow"
     "test_solver_borrow_empty_pool"
     "test_solver_borrow_exact_pool"
     "test_solver_borrow_exceeds_pool"
+    "test_failed_borrow_log"
+    "test_borrow_deposit_address"
+    "test_deposit_then_borrow"
+    "test_signature_failure"
+    "test_concurrent_borrows"
+    "test_deposit_during_borrow"
+    "test_max_intents_per_solver"
+    "test_cancel_intent"
     # Repayment validation (4 tests in file)
     "test_partial_repayment"
     # Lender profit and yield
     "test_lender_profit"
+    "test_protocol_fees"
     # Redemption queue tests
     "test_fifo_redemption_queue"
     "test_single_lender_queue"
     "test_multi_lender_queue"
     "test_borrow_with_redemption"
+    "test_borrow_cooldown"
     "test_half_redemptions"
+    "test_redeem_all"
+    "test_burn_on_queue"
+    "test_default_during_queue"
+    "test_force_process_with_loss"
+    "test_repay_auto_process"
     # Multi-party scenarios
     "test_multi_solver"
     "test_complex_multi_lender_scenario"
@@ -35,6 +35,9 @@ pub const CONTRACT_WASM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/targe
 /// Path to the compiled mock FT contract WASM.
 pub const MOCK_FT_WASM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../mock_ft/target/near/mock_ft.wasm");
 
+/// Decimals of the mock USDC asset.
+pub const ASSET_DECIMALS: u8 = 6;
+
 /// Extra decimals for vault share precision (10^3 = 1000 multiplier).
 pub const EXTRA_DECIMALS: u8 = 3;
 
@@ -42,6 +45,13 @@ pub const EXTRA_DECIMALS: u8 = 3;
 #[allow(dead_code)]
 pub const SOLVER_BORROW_AMOUNT: u128 = 5_000_000;
 
+/// NEAR attached to `redeem`/`withdraw` so the request can be queued.
+///
+/// Matches the contract's `QUEUE_STORAGE_DEPOSIT`; refunded on fulfillment,
+/// or immediately (minus 1 yoctoNEAR) when the request executes right away.
+#[allow(dead_code)]
+pub const REDEEM_DEPOSIT: NearToken = NearToken::from_millinear(5);
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -126,9 +136,36 @@ pub async fn deploy_mock_ft(
     genesis_account_id: &AccountId,
     genesis_signer: &Arc<Signer>,
     total_supply: &str,
+) -> Result<AccountId, Box<dyn std::error::Error + Send + Sync>> {
+    deploy_named_mock_ft(network_config, genesis_account_id, genesis_signer, "usdc", total_supply).await
+}
+
+/// Deploys a mock NEP-141 fungible token under `<prefix>.<genesis>`.
+///
+/// Same as [`deploy_mock_ft`], but lets tests deploy tokens other than the
+/// vault's USDC asset.
+///
+/// # Arguments
+///
+/// * `network_config` - Network connection configuration
+/// * `genesis_account_id` - Account to fund the FT account
+/// * `genesis_signer` - Signer for the genesis account
+/// * `prefix` - Sub-account name and symbol of the token (e.g. "usdc")
+/// * `total_supply` - Initial token supply as a string
+///
+/// # Returns
+///
+/// The account ID of the deployed FT contract.
+#[allow(dead_code)]
+pub async fn deploy_named_mock_ft(
+    network_config: &NetworkConfig,
+    genesis_account_id: &AccountId,
+    genesis_signer: &Arc<Signer>,
+    prefix: &str,
+    total_supply: &str,
 ) -> Result<AccountId, Box<dyn std::error::Error + Send + Sync>> {
     // Create mock FT account
-    let ft_id: AccountId = format!("usdc.{}", genesis_account_id).parse()?;
+    let ft_id: AccountId = format!("{}.{}", prefix, genesis_account_id).parse()?;
     let ft_secret_key = signer::generate_secret_key()?;
 
     Account::create_account(ft_id.clone())
@@ -152,12 +189,12 @@ pub async fn deploy_mock_ft(
             "total_supply": total_supply,
             "metadata": {
                 "spec": "ft-1.0.0",
-                "name": "Mock USDC",
-                "symbol": "USDC",
+                "name": format!("Mock {}", prefix.to_uppercase()),
+                "symbol": prefix.to_uppercase(),
                 "icon": null,
                 "reference": null,
                 "reference_hash": null,
-                "decimals": 6
+                "decimals": ASSET_DECIMALS
             }
         }))?
         .with_signer(ft_signer)
@@ -223,8 +260,9 @@ pub async fn deploy_vault_contract(
             "icon": null,
             "reference": null,
             "reference_hash": null,
-            "decimals": 24
+            "decimals": ASSET_DECIMALS + EXTRA_DECIMALS
         },
+        "asset_decimals": ASSET_DECIMALS,
         "extra_decimals": EXTRA_DECIMALS,
         "solver_fee": 1
     });
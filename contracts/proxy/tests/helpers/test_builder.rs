@@ -296,7 +296,7 @@ pub async fn solver_borrow(
 
     let intent_params = json!({
         "intent_data": format!("intent-{}", intent_hash),
-        "_solver_deposit_address": solver_id,
+        "solver_deposit_address": solver_id,
         "user_deposit_hash": intent_hash,
         "amount": amount.to_string()
     });
@@ -396,7 +396,7 @@ pub async fn redeem_shares(
             "memo": null
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender_id.clone(), lender_signer.clone())
         .send_to(network_config)
         .await?;
@@ -582,14 +582,12 @@ pub async fn calculate_expected_shares_for_deposit(
 
     // Calculate denominator: total_assets + total_borrowed + expected_yield
     let denominator = total_assets
-        .checked_add(total_borrowed)
-        .unwrap_or(u128::MAX)
-        .checked_add(expected_yield)
-        .unwrap_or(u128::MAX)
+        .saturating_add(total_borrowed)
+        .saturating_add(expected_yield)
         .max(1);
 
     // Calculate shares
-    let shares = (deposit_amount as u128)
+    let shares = deposit_amount
         .checked_mul(total_supply_u128)
         .unwrap_or(0)
         .checked_div(denominator)
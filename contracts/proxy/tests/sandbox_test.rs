@@ -12,6 +12,7 @@
 //! | `test_contract_deployment` | Deploys vault contract | Vault deploys without errors |
 //! | `test_approve_codehash` | Owner approves TEE codehash | Codehash approved successfully |
 //! | `test_vault_initialization` | Checks vault initial state | Zero assets, zero shares, correct metadata |
+//! | `test_min_deposit_from_asset_metadata` | Reads the minimum deposit after init | Derived from the mock FT's 6 decimals |
 //! | `test_vault_conversion_functions` | Tests share conversion | Empty vault uses extra_decimals multiplier |
 //!
 //! ## No Lender/Solver Interaction
@@ -139,7 +140,7 @@ async fn test_approve_codehash() -> Result<(), Box<dyn std::error::Error + Send
 ///
 /// # Expected Outcome
 ///
-/// - Share token metadata: name=vUSDC, decimals=24
+/// - Share token metadata: name=vUSDC, decimals=ASSET_DECIMALS + EXTRA_DECIMALS
 /// - Underlying asset: usdc.* address
 /// - Total assets: 0 (no deposits yet)
 /// - Total shares: 0 (no shares minted)
@@ -164,7 +165,16 @@ async fn test_vault_initialization() -> Result<(), Box<dyn std::error::Error + S
     
     assert_eq!(metadata.data["name"], "USDC Vault Shares");
     assert_eq!(metadata.data["symbol"], "vUSDC");
-    assert_eq!(metadata.data["decimals"], 24);
+    assert_eq!(metadata.data["decimals"], ASSET_DECIMALS + EXTRA_DECIMALS);
+
+    let config: Data<serde_json::Value> = vault_contract
+        .call_function("get_vault_config", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(config.data["asset_decimals"], ASSET_DECIMALS);
+    assert_eq!(config.data["extra_decimals"], EXTRA_DECIMALS);
+    assert_eq!(config.data["paused"], false);
 
     // Check underlying asset
     let asset: Data<String> = vault_contract
@@ -206,6 +216,38 @@ async fn test_vault_initialization() -> Result<(), Box<dyn std::error::Error + S
     Ok(())
 }
 
+/// Tests that the minimum deposit follows the asset's own decimals.
+///
+/// # Scenario
+///
+/// Deploys the vault against the mock USDC token, whose `ft_metadata` is
+/// read by the callback `init` schedules, then reads the vault config.
+///
+/// # Expected Outcome
+///
+/// - `asset_decimals` is 6
+/// - `min_deposit` is one whole token (1_000_000)
+#[tokio::test]
+async fn test_min_deposit_from_asset_metadata() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let sandbox = near_sandbox::Sandbox::start_sandbox().await?;
+    let network_config = create_network_config(&sandbox);
+    let (genesis_account_id, genesis_signer) = setup_genesis_account().await;
+
+    let contract_id = deploy_vault_contract(&network_config, &genesis_account_id, &genesis_signer).await?;
+    sleep(Duration::from_millis(1200)).await;
+
+    let config: Data<serde_json::Value> = Contract(contract_id)
+        .call_function("get_vault_config", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(config.data["asset_decimals"], 6);
+    assert_eq!(config.data["min_deposit"], "1000000");
+    assert_eq!(config.data["min_first_deposit"], "1000000");
+
+    Ok(())
+}
+
 /// Tests vault share conversion functions on an empty vault.
 ///
 /// # Scenario
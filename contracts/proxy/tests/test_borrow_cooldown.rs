@@ -0,0 +1,114 @@
+//! # Borrow Cooldown Test
+//!
+//! Tests that `borrow_cooldown_blocks` keeps solvers from borrowing right
+//! after a redemption pays out, and that borrowing resumes once the window
+//! has passed.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_borrow_cooldown` | Solver borrows right after a lender redeems, then again after the cooldown | First borrow rejected, second succeeds |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Owner sets borrow_cooldown_blocks = 20
+//! 2. Lender deposits 100 USDC and redeems half
+//! 3. Solver tries to borrow 10 USDC -> rejected (cooldown)
+//! 4. Wait until get_borrow_cooldown_remaining == 0
+//! 5. Solver borrows 10 USDC -> succeeds
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{
+    deposit_to_vault, get_total_assets, redeem_shares, solver_borrow, TestScenarioBuilder,
+};
+use near_api::Data;
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Reads `get_borrow_cooldown_remaining` from the vault.
+async fn cooldown_remaining(
+    builder: &TestScenarioBuilder,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    let remaining: Data<u64> = builder
+        .vault_contract()
+        .call_function("get_borrow_cooldown_remaining", json!({}))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    Ok(remaining.data)
+}
+
+/// Tests that borrows are blocked during the cooldown and allowed after it.
+///
+/// # Expected Outcome
+///
+/// - The borrow right after the redemption leaves total_assets unchanged
+/// - The borrow after the cooldown reduces total_assets by 10 USDC
+#[tokio::test]
+async fn test_borrow_cooldown() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .create_account("solver")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 100_000_000u128; // 100 USDC
+    let borrow_amount = 10_000_000u128; // 10 USDC
+
+    // =========================================================================
+    // CONFIGURE COOLDOWN
+    // =========================================================================
+    builder
+        .vault_contract()
+        .call_function("set_borrow_cooldown_blocks", json!({ "blocks": 20 }))?
+        .transaction()
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(builder.network_config())
+        .await?;
+
+    // =========================================================================
+    // LENDER DEPOSITS AND REDEEMS HALF
+    // =========================================================================
+    let shares = deposit_to_vault(&builder, "lender", deposit_amount).await?;
+    redeem_shares(&builder, "lender", shares / 2).await?;
+
+    let assets_after_redeem = get_total_assets(&builder).await?;
+    assert_eq!(assets_after_redeem, deposit_amount / 2);
+
+    // =========================================================================
+    // BORROW DURING COOLDOWN IS REJECTED
+    // =========================================================================
+    let remaining = cooldown_remaining(&builder).await?;
+    println!("cooldown remaining after redeem: {} blocks", remaining);
+    assert!(remaining > 0, "redemption should start the cooldown");
+
+    solver_borrow(&builder, borrow_amount, "hash-cooldown-1").await?;
+    assert_eq!(get_total_assets(&builder).await?, assets_after_redeem);
+
+    // =========================================================================
+    // BORROW AFTER COOLDOWN SUCCEEDS
+    // =========================================================================
+    let mut attempts = 0;
+    while cooldown_remaining(&builder).await? > 0 {
+        attempts += 1;
+        assert!(attempts <= 60, "cooldown did not elapse");
+        sleep(Duration::from_millis(1000)).await;
+    }
+
+    solver_borrow(&builder, borrow_amount, "hash-cooldown-2").await?;
+    assert_eq!(
+        get_total_assets(&builder).await?,
+        assets_after_redeem - borrow_amount
+    );
+
+    Ok(())
+}
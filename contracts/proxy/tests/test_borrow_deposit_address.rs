@@ -0,0 +1,109 @@
+//! # Borrow Deposit Address Test
+//!
+//! Tests that `new_intent` sends borrowed liquidity to `solver_deposit_address`
+//! while the intent stays owned by the calling solver.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_borrow_deposit_address` | Solver hot wallet borrows with a separate settlement account as deposit address | Settlement account receives the funds, intent belongs to the solver |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Lender deposits 100 USDC
+//! 2. Solver calls new_intent with solver_deposit_address = settlement
+//! 3. Settlement account receives 10 USDC, solver's balance is unchanged
+//! 4. get_intents_by_solver(solver) returns the intent
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{deposit_to_vault, get_balance, TestScenarioBuilder};
+use near_api::Data;
+use serde_json::{json, Value};
+use tokio::time::{sleep, Duration};
+
+/// Tests borrowing on behalf of a settlement account.
+///
+/// # Expected Outcome
+///
+/// - The settlement account's USDC balance grows by the borrow amount
+/// - The solver's USDC balance is unchanged
+/// - The intent is listed under the solver, not the settlement account
+#[tokio::test]
+async fn test_borrow_deposit_address() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .create_account("solver")
+        .await?
+        .create_account("settlement")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 100_000_000u128; // 100 USDC
+    let borrow_amount = 10_000_000u128; // 10 USDC
+
+    deposit_to_vault(&builder, "lender", deposit_amount).await?;
+
+    let (solver_id, solver_signer, _) = builder
+        .get_account("solver")
+        .ok_or("Account solver not found")?;
+    let (settlement_id, _, _) = builder
+        .get_account("settlement")
+        .ok_or("Account settlement not found")?;
+    let solver_before = get_balance(&builder, "solver").await?;
+    let settlement_before = get_balance(&builder, "settlement").await?;
+
+    // =========================================================================
+    // SOLVER BORROWS TO THE SETTLEMENT ACCOUNT
+    // =========================================================================
+    builder
+        .vault_contract()
+        .call_function("new_intent", json!({
+            "intent_data": "intent-settlement",
+            "solver_deposit_address": settlement_id,
+            "user_deposit_hash": "hash-settlement",
+            "amount": borrow_amount.to_string()
+        }))?
+        .transaction()
+        .with_signer(solver_id.clone(), solver_signer.clone())
+        .send_to(builder.network_config())
+        .await?;
+
+    sleep(Duration::from_millis(1200)).await;
+
+    // =========================================================================
+    // VERIFY FUNDS AND OWNERSHIP
+    // =========================================================================
+    assert_eq!(
+        get_balance(&builder, "settlement").await? - settlement_before,
+        borrow_amount
+    );
+    assert_eq!(get_balance(&builder, "solver").await?, solver_before);
+
+    let intents: Data<Vec<Value>> = builder
+        .vault_contract()
+        .call_function("get_intents_by_solver", json!({ "solver_id": solver_id }))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    assert_eq!(intents.data.len(), 1);
+    assert_eq!(intents.data[0]["intent"]["user_deposit_hash"], "hash-settlement");
+
+    let settlement_count: Data<u32> = builder
+        .vault_contract()
+        .call_function("get_solver_intent_count", json!({ "solver_id": settlement_id }))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    assert_eq!(settlement_count.data, 0);
+
+    Ok(())
+}
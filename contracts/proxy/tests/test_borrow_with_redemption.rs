@@ -148,7 +148,7 @@ async fn test_borrow_with_redemption() -> Result<(), Box<dyn std::error::Error +
     let _intent1 = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-s1",
-            "_solver_deposit_address": solver1_id,
+            "solver_deposit_address": solver1_id,
             "user_deposit_hash": "hash-s1",
             "amount": solver1_borrow_amount.to_string()
         }))?
@@ -180,7 +180,7 @@ async fn test_borrow_with_redemption() -> Result<(), Box<dyn std::error::Error +
             "receiver_id": lender1_id
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender1_id.clone(), lender1_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -205,7 +205,7 @@ async fn test_borrow_with_redemption() -> Result<(), Box<dyn std::error::Error +
     let _intent2_result = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-s2",
-            "_solver_deposit_address": solver2_id,
+            "solver_deposit_address": solver2_id,
             "user_deposit_hash": "hash-s2",
             "amount": solver2_borrow_amount.to_string()
         }))?
@@ -314,7 +314,7 @@ async fn test_borrow_with_redemption() -> Result<(), Box<dyn std::error::Error +
         let _intent2_success = vault_contract
             .call_function("new_intent", json!({
                 "intent_data": "intent-s2-success",
-                "_solver_deposit_address": solver2_id,
+                "solver_deposit_address": solver2_id,
                 "user_deposit_hash": "hash-s2-success",
                 "amount": solver2_new_borrow.to_string()
             }))?
@@ -0,0 +1,122 @@
+//! # Burn-on-Queue Test
+//!
+//! Tests that with `burn_on_queue` enabled, a lender cannot transfer away
+//! shares backing a queued redemption, and the entry still pays out.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_burn_on_queue` | Lender queues a redemption, then tries to `ft_transfer` the shares | Transfer fails, queued entry pays the lender in full |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Owner enables burn_on_queue
+//! 2. Lender deposits 5 USDC, solver borrows all of it
+//! 3. Lender redeems all shares -> QUEUED, shares moved to escrow
+//! 4. Lender ft_transfer's the shares to another account -> fails
+//! 5. Solver repays, queue is processed -> lender receives the assets
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{
+    deposit_to_vault, get_balance, get_shares, process_redemption_queue, redeem_shares,
+    solver_borrow, solver_repay, TestScenarioBuilder,
+};
+use near_api::{Data, NearToken};
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Tests that escrowed queue shares cannot be moved before processing.
+///
+/// # Expected Outcome
+///
+/// - The lender's share balance drops to 0 at queue time and the transfer fails
+/// - The other account never receives shares
+/// - After processing, the lender's USDC balance grows by the queued assets
+#[tokio::test]
+async fn test_burn_on_queue() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .create_account("other")
+        .await?
+        .create_account("solver")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 5_000_000u128; // 5 USDC
+    let intent_yield = deposit_amount / 100; // 1%
+
+    builder
+        .vault_contract()
+        .call_function("set_burn_on_queue", json!({ "enabled": true }))?
+        .transaction()
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(builder.network_config())
+        .await?;
+
+    // =========================================================================
+    // LENDER QUEUES A REDEMPTION
+    // =========================================================================
+    let lender_shares = deposit_to_vault(&builder, "lender", deposit_amount).await?;
+    solver_borrow(&builder, deposit_amount, "hash-escrow").await?;
+    redeem_shares(&builder, "lender", lender_shares).await?;
+
+    let (lender_id, lender_signer, _) = builder
+        .get_account("lender")
+        .ok_or("Account lender not found")?;
+    let escrowed: Data<String> = builder
+        .vault_contract()
+        .call_function("get_escrowed_shares", json!({ "account_id": lender_id }))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    assert_eq!(escrowed.data, lender_shares.to_string());
+    assert_eq!(get_shares(&builder, "lender").await?, 0);
+
+    // =========================================================================
+    // TRANSFER-AWAY ATTEMPT FAILS
+    // =========================================================================
+    let (other_id, _, _) = builder
+        .get_account("other")
+        .ok_or("Account other not found")?;
+    let outcome = builder
+        .vault_contract()
+        .call_function("ft_transfer", json!({
+            "receiver_id": other_id,
+            "amount": lender_shares.to_string()
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(lender_id.clone(), lender_signer.clone())
+        .send_to(builder.network_config())
+        .await?;
+    let status_str = format!("{:?}", outcome.status);
+    assert!(
+        status_str.contains("Failure"),
+        "transfer of escrowed shares should fail, got {status_str}"
+    );
+    sleep(Duration::from_millis(1200)).await;
+    assert_eq!(get_shares(&builder, "other").await?, 0);
+
+    // =========================================================================
+    // REPAY AND PROCESS QUEUE
+    // =========================================================================
+    let lender_assets_before = get_balance(&builder, "lender").await?;
+    solver_repay(&builder, 0, deposit_amount, intent_yield).await?;
+    process_redemption_queue(&builder).await?;
+
+    let received = get_balance(&builder, "lender").await? - lender_assets_before;
+    println!("lender received {} after processing", received);
+    assert!(received >= deposit_amount, "queued entry should pay out in full");
+    assert_eq!(get_shares(&builder, "lender").await?, 0);
+
+    Ok(())
+}
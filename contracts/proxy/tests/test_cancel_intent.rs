@@ -0,0 +1,95 @@
+//! # Cancel Intent Test
+//!
+//! Tests that a solver can cancel a freshly borrowed intent by returning the
+//! principal through a `cancel_repay` transfer, without paying yield.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_cancel_intent` | Solver borrows, then immediately returns the principal with `cancel_repay` | Intent is gone and `total_assets` is back to the deposit |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Lender deposits 100 USDC
+//! 2. Solver borrows 10 USDC (intent 0) -> total_assets 90 USDC
+//! 3. Solver ft_transfer_calls 10 USDC with a cancel_repay message
+//! 4. Intent 0 is removed, total_assets is 100 USDC again
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{deposit_to_vault, get_total_assets, solver_borrow, TestScenarioBuilder};
+use near_api::{Data, NearGas, NearToken};
+use serde_json::{json, Value};
+use tokio::time::{sleep, Duration};
+
+/// Tests cancelling an intent within the cancellation window.
+///
+/// # Expected Outcome
+///
+/// - The solver has no open intents after the cancellation
+/// - `total_assets` equals the lender's deposit again
+#[tokio::test]
+async fn test_cancel_intent() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .create_account("solver")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 100_000_000u128; // 100 USDC
+    let borrow_amount = 10_000_000u128; // 10 USDC
+
+    deposit_to_vault(&builder, "lender", deposit_amount).await?;
+    solver_borrow(&builder, borrow_amount, "hash-cancel").await?;
+    assert_eq!(
+        get_total_assets(&builder).await?,
+        deposit_amount - borrow_amount
+    );
+
+    // =========================================================================
+    // RETURN THE PRINCIPAL WITH CANCEL_REPAY
+    // =========================================================================
+    let (solver_id, solver_signer, _) = builder
+        .get_account("solver")
+        .ok_or("Account solver not found")?;
+    let network_config = builder.network_config();
+
+    builder
+        .ft_contract()
+        .call_function("ft_transfer_call", json!({
+            "receiver_id": builder.vault_id(),
+            "amount": borrow_amount.to_string(),
+            "msg": json!({ "cancel_repay": { "intent_index": "0" } }).to_string()
+        }))?
+        .transaction()
+        .gas(NearGas::from_tgas(100))
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(solver_id.clone(), solver_signer.clone())
+        .send_to(network_config)
+        .await?;
+
+    sleep(Duration::from_millis(1200)).await;
+
+    // =========================================================================
+    // VERIFY THE INTENT IS GONE
+    // =========================================================================
+    assert_eq!(get_total_assets(&builder).await?, deposit_amount);
+
+    let intents: Data<Vec<Value>> = builder
+        .vault_contract()
+        .call_function("get_intents_by_solver", json!({ "solver_id": solver_id }))?
+        .read_only()
+        .fetch_from(network_config)
+        .await?;
+    assert!(intents.data.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! # Default During Queue Test
+//!
+//! Tests that a redemption queued while all liquidity is borrowed is repriced
+//! when one of the borrows defaults before the queue is processed.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_default_during_queue` | Lender queues a full redemption, one of two borrows is written off, the other is repaid | Lender is paid what the shares are worth after the loss, not the value stored at queue time |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Lender deposits 10 USDC
+//! 2. Solver borrows 5 USDC twice (all liquidity)
+//! 3. Lender redeems everything -> QUEUED at 10.1 USDC (incl. expected yield)
+//! 4. Owner force-writes off the first borrow
+//! 5. Solver repays the second borrow with 1% yield (5.05 USDC)
+//! 6. Queue is processed -> lender receives 5.05 USDC
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{
+    deposit_to_vault, get_balance, get_shares, get_total_assets, process_redemption_queue,
+    redeem_shares, solver_borrow, solver_repay, TestScenarioBuilder,
+};
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Tests the queue payout after a default between queueing and processing.
+///
+/// # Expected Outcome
+///
+/// - The payout is clamped to the post-write-off share value (5.05 USDC)
+/// - The lender holds no shares and the vault holds no idle assets afterwards
+#[tokio::test]
+async fn test_default_during_queue() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .create_account("solver")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 10_000_000u128; // 10 USDC
+    let borrow_amount = 5_000_000u128; // 5 USDC
+    let intent_yield = borrow_amount / 100; // 1%
+
+    // =========================================================================
+    // LENDER DEPOSITS, SOLVER BORROWS EVERYTHING
+    // =========================================================================
+    let shares = deposit_to_vault(&builder, "lender", deposit_amount).await?;
+    solver_borrow(&builder, borrow_amount, "hash-default-1").await?;
+    solver_borrow(&builder, borrow_amount, "hash-default-2").await?;
+    assert_eq!(get_total_assets(&builder).await?, 0);
+
+    // =========================================================================
+    // LENDER QUEUES A FULL REDEMPTION
+    // =========================================================================
+    redeem_shares(&builder, "lender", shares).await?;
+    let lender_before = get_balance(&builder, "lender").await?;
+
+    // =========================================================================
+    // FIRST BORROW DEFAULTS, SECOND IS REPAID
+    // =========================================================================
+    builder
+        .vault_contract()
+        .call_function("write_off_intent", json!({ "index": "0", "force": true }))?
+        .transaction()
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(builder.network_config())
+        .await?;
+    sleep(Duration::from_millis(1200)).await;
+
+    solver_repay(&builder, 1, borrow_amount, intent_yield).await?;
+
+    // =========================================================================
+    // PROCESS QUEUE
+    // =========================================================================
+    process_redemption_queue(&builder).await?;
+
+    let received = get_balance(&builder, "lender").await? - lender_before;
+    println!("lender received {} after the default", received);
+    assert_eq!(received, borrow_amount + intent_yield);
+    assert_eq!(get_shares(&builder, "lender").await?, 0);
+    assert_eq!(get_total_assets(&builder).await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//! # Deposit Then Borrow Test
+//!
+//! Tests the `deposit_then_borrow` transfer action, which mints shares for a
+//! solver's deposit and opens an intent against the pool in one call.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_deposit_then_borrow` | Solver deposits into an empty vault and borrows part of it back | Solver holds shares and an open intent |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Solver receives 50 USDC
+//! 2. Solver ft_transfer_calls 50 USDC with a deposit_then_borrow message
+//! 3. Vault mints shares for the 50 USDC, then lends 20 USDC of it back
+//! 4. Solver holds shares and 20 USDC; the vault holds 30 USDC and one intent
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{get_balance, get_shares, get_total_assets, TestScenarioBuilder};
+use near_api::{Data, NearGas, NearToken};
+use serde_json::{json, Value};
+use tokio::time::{sleep, Duration};
+
+/// Tests depositing and borrowing in a single `ft_transfer_call`.
+///
+/// The vault starts empty, so the borrow can only succeed if the deposit is
+/// credited before the borrow checks run.
+///
+/// # Expected Outcome
+///
+/// - The solver holds vault shares for the full deposit
+/// - The solver received the borrowed amount
+/// - One intent is open for the solver and `total_assets` is deposit - borrow
+#[tokio::test]
+async fn test_deposit_then_borrow() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("solver")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 50_000_000u128; // 50 USDC
+    let borrow_amount = 20_000_000u128; // 20 USDC
+
+    let (solver_id, solver_signer, _) = builder
+        .get_account("solver")
+        .ok_or("Account solver not found")?;
+    let ft_contract = builder.ft_contract();
+    let network_config = builder.network_config();
+
+    ft_contract
+        .call_function("ft_transfer", json!({
+            "receiver_id": solver_id,
+            "amount": deposit_amount.to_string()
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(network_config)
+        .await?;
+
+    // =========================================================================
+    // DEPOSIT AND BORROW IN ONE TRANSFER
+    // =========================================================================
+    let msg = json!({
+        "deposit_then_borrow": {
+            "deposit": {},
+            "borrow": {
+                "intent_data": "intent-combined",
+                "solver_deposit_address": solver_id,
+                "user_deposit_hash": "hash-combined",
+                "amount": borrow_amount.to_string()
+            }
+        }
+    });
+    ft_contract
+        .call_function("ft_transfer_call", json!({
+            "receiver_id": builder.vault_id(),
+            "amount": deposit_amount.to_string(),
+            "msg": msg.to_string()
+        }))?
+        .transaction()
+        .gas(NearGas::from_tgas(300))
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(solver_id.clone(), solver_signer.clone())
+        .send_to(network_config)
+        .await?;
+
+    sleep(Duration::from_millis(1200)).await;
+
+    // =========================================================================
+    // VERIFY SHARES AND INTENT
+    // =========================================================================
+    assert!(get_shares(&builder, "solver").await? > 0);
+    assert_eq!(get_balance(&builder, "solver").await?, borrow_amount);
+    assert_eq!(
+        get_total_assets(&builder).await?,
+        deposit_amount - borrow_amount
+    );
+
+    let intents: Data<Vec<Value>> = builder
+        .vault_contract()
+        .call_function("get_intents_by_solver", json!({ "solver_id": solver_id }))?
+        .read_only()
+        .fetch_from(network_config)
+        .await?;
+    assert_eq!(intents.data.len(), 1);
+    assert_eq!(intents.data[0]["intent"]["user_deposit_hash"], "hash-combined");
+    assert_eq!(
+        intents.data[0]["intent"]["borrow_amount"],
+        borrow_amount.to_string()
+    );
+
+    Ok(())
+}
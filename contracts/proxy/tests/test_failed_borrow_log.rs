@@ -0,0 +1,86 @@
+//! # Failed Borrow Log Test
+//!
+//! Tests that a borrow whose `ft_transfer` to the solver fails is rolled
+//! back and recorded in `get_failed_borrows`.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_failed_borrow_log` | Solver without FT storage registration borrows | Transfer fails, total_assets restored, failure logged |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Lender deposits 100 USDC
+//! 2. Solver account is created without registering with the USDC token
+//! 3. Solver borrows 10 USDC -> ft_transfer fails, borrow rolled back
+//! 4. get_failed_borrows returns the attempt
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{deposit_to_vault, get_total_assets, solver_borrow, TestScenarioBuilder};
+use near_api::Data;
+use serde_json::{json, Value};
+
+/// Tests that an unregistered solver's failed borrow is recorded.
+///
+/// # Expected Outcome
+///
+/// - total_assets is back to the deposit amount
+/// - No intent is created
+/// - One failed borrow entry with the solver, amount and hash
+#[tokio::test]
+async fn test_failed_borrow_log() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // The solver is created after register_accounts, so it has no FT storage
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .register_accounts()
+        .await?
+        .create_account("solver")
+        .await?;
+
+    let deposit_amount = 100_000_000u128; // 100 USDC
+    let borrow_amount = 10_000_000u128; // 10 USDC
+
+    deposit_to_vault(&builder, "lender", deposit_amount).await?;
+
+    // =========================================================================
+    // BORROW TRANSFER FAILS
+    // =========================================================================
+    solver_borrow(&builder, borrow_amount, "hash-unregistered").await?;
+    assert_eq!(get_total_assets(&builder).await?, deposit_amount);
+
+    let intents: Data<Vec<Value>> = builder
+        .vault_contract()
+        .call_function("get_intents", json!({}))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    assert!(intents.data.is_empty());
+
+    // =========================================================================
+    // FAILURE IS LOGGED
+    // =========================================================================
+    let (solver_id, _, _) = builder
+        .get_account("solver")
+        .ok_or("Account solver not found")?;
+    let failed: Data<Vec<Value>> = builder
+        .vault_contract()
+        .call_function("get_failed_borrows", json!({}))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    println!("failed borrows: {:?}", failed.data);
+    assert_eq!(failed.data.len(), 1);
+    assert_eq!(failed.data[0]["solver_id"], solver_id.to_string());
+    assert_eq!(failed.data[0]["amount"], borrow_amount.to_string());
+    assert_eq!(failed.data[0]["user_deposit_hash"], "hash-unregistered");
+
+    Ok(())
+}
@@ -193,7 +193,7 @@ async fn test_fifo_redemption_queue() -> Result<(), Box<dyn std::error::Error +
     let intent_outcome = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-1",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-1",
             "amount": solver_borrow_amount.to_string()
         }))?
@@ -224,7 +224,7 @@ async fn test_fifo_redemption_queue() -> Result<(), Box<dyn std::error::Error +
             "memo": null
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender2_id.clone(), lender2_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -248,7 +248,7 @@ async fn test_fifo_redemption_queue() -> Result<(), Box<dyn std::error::Error +
             "memo": null
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender1_id.clone(), lender1_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -0,0 +1,99 @@
+//! # Force Process With Loss Test
+//!
+//! Tests that `force_process_with_loss` lets a lender stuck behind a defaulted
+//! borrow exit with their pro-rata share of the idle assets.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_force_process_with_loss` | Lender queues a full redemption, a borrow defaults while another is still out, owner force-processes the queue | Lender receives the idle 5 USDC instead of waiting for the outstanding borrow |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Lenders 1 and 2 deposit 10 USDC each
+//! 2. Solver borrows 5 USDC and 10 USDC (5 USDC idle)
+//! 3. Lender 1 redeems everything -> QUEUED at 10.075 USDC
+//! 4. Owner writes off the 10 USDC borrow; lender 1's shares are now worth
+//!    5.025 USDC, more than the 5 USDC idle, so the queue is stuck
+//! 5. Owner calls force_process_with_loss -> lender 1 receives 5 USDC
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{
+    deposit_to_vault, get_balance, get_shares, get_total_assets, redeem_shares, solver_borrow,
+    TestScenarioBuilder,
+};
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Tests a forced, loss-adjusted payout of a stuck queue entry.
+///
+/// # Expected Outcome
+///
+/// - The lender receives all 5 USDC of idle assets for their full position
+/// - The lender holds no shares and the other lender's shares are untouched
+#[tokio::test]
+async fn test_force_process_with_loss() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender1")
+        .await?
+        .create_account("lender2")
+        .await?
+        .create_account("solver")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 10_000_000u128; // 10 USDC
+
+    // =========================================================================
+    // LENDERS DEPOSIT, SOLVER BORROWS 15 OF 20 USDC
+    // =========================================================================
+    let shares = deposit_to_vault(&builder, "lender1", deposit_amount).await?;
+    let lender2_shares = deposit_to_vault(&builder, "lender2", deposit_amount).await?;
+    solver_borrow(&builder, 5_000_000, "hash-loss-1").await?;
+    solver_borrow(&builder, 10_000_000, "hash-loss-2").await?;
+    assert_eq!(get_total_assets(&builder).await?, 5_000_000);
+
+    // =========================================================================
+    // LENDER 1 QUEUES A FULL REDEMPTION, SECOND BORROW DEFAULTS
+    // =========================================================================
+    redeem_shares(&builder, "lender1", shares).await?;
+    let lender_before = get_balance(&builder, "lender1").await?;
+
+    builder
+        .vault_contract()
+        .call_function("write_off_intent", json!({ "index": "1", "force": true }))?
+        .transaction()
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(builder.network_config())
+        .await?;
+    sleep(Duration::from_millis(1200)).await;
+
+    // =========================================================================
+    // FORCE-PROCESS THE QUEUE
+    // =========================================================================
+    builder
+        .vault_contract()
+        .call_function("force_process_with_loss", json!({ "max_entries": 10 }))?
+        .transaction()
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(builder.network_config())
+        .await?;
+    sleep(Duration::from_millis(1200)).await;
+
+    let received = get_balance(&builder, "lender1").await? - lender_before;
+    println!("lender1 received {} after the forced payout", received);
+    assert_eq!(received, 5_000_000);
+    assert_eq!(get_shares(&builder, "lender1").await?, 0);
+    assert_eq!(get_shares(&builder, "lender2").await?, lender2_shares);
+    assert_eq!(get_total_assets(&builder).await?, 0);
+
+    Ok(())
+}
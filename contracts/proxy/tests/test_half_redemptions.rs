@@ -147,7 +147,7 @@ async fn test_half_redemptions() -> Result<(), Box<dyn std::error::Error + Send
     let _intent1 = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-1",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-1",
             "amount": lender1_deposit.to_string()
         }))?
@@ -218,7 +218,7 @@ async fn test_half_redemptions() -> Result<(), Box<dyn std::error::Error + Send
             "receiver_id": lender1_id
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender1_id.clone(), lender1_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -468,7 +468,7 @@ async fn test_half_redemptions() -> Result<(), Box<dyn std::error::Error + Send
     let _intent2 = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-2",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-2",
             "amount": borrow_amount2.to_string()
         }))?
@@ -513,7 +513,7 @@ async fn test_half_redemptions() -> Result<(), Box<dyn std::error::Error + Send
             "receiver_id": lender2_id
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender2_id.clone(), lender2_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -546,7 +546,7 @@ async fn test_half_redemptions() -> Result<(), Box<dyn std::error::Error + Send
             "receiver_id": lender1_id
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender1_id.clone(), lender1_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -660,7 +660,7 @@ async fn test_half_redemptions() -> Result<(), Box<dyn std::error::Error + Send
             "receiver_id": lender2_id
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender2_id.clone(), lender2_signer.clone())
         .send_to(&network_config)
         .await?;
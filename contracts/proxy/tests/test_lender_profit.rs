@@ -141,7 +141,7 @@ async fn test_lender_profit() -> Result<(), Box<dyn std::error::Error + Send + S
     let _intent = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-profit",
             "amount": SOLVER_BORROW_AMOUNT.to_string()
         }))?
@@ -165,7 +165,7 @@ async fn test_lender_profit() -> Result<(), Box<dyn std::error::Error + Send + S
             "memo": null
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender1_id.clone(), lender1_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -322,7 +322,7 @@ async fn test_lender_profit() -> Result<(), Box<dyn std::error::Error + Send + S
             "memo": null
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender2_id.clone(), lender2_signer.clone())
         .send_to(&network_config)
         .await?;
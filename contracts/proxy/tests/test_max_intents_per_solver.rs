@@ -0,0 +1,107 @@
+//! # Max Intents Per Solver Test
+//!
+//! Tests that `max_intents_per_solver` caps how many open intents one solver
+//! can hold, and that repaying an intent frees a slot.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_max_intents_per_solver` | Solver opens intents up to a limit of 2, tries a third, repays one, retries | Third borrow rejected, succeeds after the repayment |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Owner sets max_intents_per_solver = 2
+//! 2. Lender deposits 100 USDC
+//! 3. Solver borrows 10 USDC twice -> 2 open intents
+//! 4. Solver tries a third borrow -> rejected (limit)
+//! 5. Solver repays intent 0
+//! 6. Solver retries the third borrow -> succeeds
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{
+    deposit_to_vault, get_total_assets, solver_borrow, solver_repay, TestScenarioBuilder,
+};
+use near_api::Data;
+use serde_json::json;
+
+/// Reads `get_solver_intent_count` for the solver account.
+async fn solver_intent_count(
+    builder: &TestScenarioBuilder,
+) -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
+    let (solver_id, _, _) = builder
+        .get_account("solver")
+        .ok_or("Account solver not found")?;
+    let count: Data<u32> = builder
+        .vault_contract()
+        .call_function("get_solver_intent_count", json!({ "solver_id": solver_id }))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    Ok(count.data)
+}
+
+/// Tests that the per-solver intent limit blocks and then releases borrows.
+///
+/// # Expected Outcome
+///
+/// - The third borrow leaves total_assets and the intent count unchanged
+/// - After repaying one intent the third borrow goes through
+#[tokio::test]
+async fn test_max_intents_per_solver() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .create_account("solver")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 100_000_000u128; // 100 USDC
+    let borrow_amount = 10_000_000u128; // 10 USDC
+    let intent_yield = borrow_amount / 100; // 1%
+
+    // =========================================================================
+    // CONFIGURE LIMIT
+    // =========================================================================
+    builder
+        .vault_contract()
+        .call_function("set_max_intents_per_solver", json!({ "max_intents": 2 }))?
+        .transaction()
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(builder.network_config())
+        .await?;
+
+    // =========================================================================
+    // SOLVER FILLS ITS SLOTS
+    // =========================================================================
+    deposit_to_vault(&builder, "lender", deposit_amount).await?;
+    solver_borrow(&builder, borrow_amount, "hash-limit-1").await?;
+    solver_borrow(&builder, borrow_amount, "hash-limit-2").await?;
+    assert_eq!(solver_intent_count(&builder).await?, 2);
+
+    // =========================================================================
+    // THIRD BORROW IS REJECTED
+    // =========================================================================
+    let assets_at_limit = get_total_assets(&builder).await?;
+    solver_borrow(&builder, borrow_amount, "hash-limit-3").await?;
+    assert_eq!(solver_intent_count(&builder).await?, 2);
+    assert_eq!(get_total_assets(&builder).await?, assets_at_limit);
+
+    // =========================================================================
+    // REPAYING FREES A SLOT
+    // =========================================================================
+    solver_repay(&builder, 0, borrow_amount, intent_yield).await?;
+    assert_eq!(solver_intent_count(&builder).await?, 1);
+
+    solver_borrow(&builder, borrow_amount, "hash-limit-3").await?;
+    assert_eq!(solver_intent_count(&builder).await?, 2);
+
+    Ok(())
+}
@@ -141,7 +141,7 @@ async fn test_multi_lender_queue() -> Result<(), Box<dyn std::error::Error + Sen
     let _intent1 = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-1",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-1",
             "amount": SOLVER_BORROW_AMOUNT.to_string()
         }))?
@@ -227,7 +227,7 @@ async fn test_multi_lender_queue() -> Result<(), Box<dyn std::error::Error + Sen
             "memo": null
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender1_id.clone(), lender1_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -260,7 +260,7 @@ async fn test_multi_lender_queue() -> Result<(), Box<dyn std::error::Error + Sen
                     "memo": null
                 }))?
                 .transaction()
-                .deposit(NearToken::from_yoctonear(1))
+                .deposit(REDEEM_DEPOSIT)
                 .with_signer(lender1_id.clone(), lender1_signer.clone())
                 .send_to(&network_config)
                 .await?;
@@ -381,7 +381,7 @@ async fn test_multi_lender_queue() -> Result<(), Box<dyn std::error::Error + Sen
     let _intent2 = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-2",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-2",
             "amount": solver2_borrow_amount.to_string()
         }))?
@@ -414,7 +414,7 @@ async fn test_multi_lender_queue() -> Result<(), Box<dyn std::error::Error + Sen
                 "memo": null
             }))?
             .transaction()
-            .deposit(NearToken::from_yoctonear(1))
+            .deposit(REDEEM_DEPOSIT)
             .with_signer(lender2_id.clone(), lender2_signer.clone())
             .send_to(&network_config)
             .await?;
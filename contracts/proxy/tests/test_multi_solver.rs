@@ -143,7 +143,7 @@ async fn test_multi_solver() -> Result<(), Box<dyn std::error::Error + Send + Sy
     let _intent1 = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-s1",
-            "_solver_deposit_address": solver1_id,
+            "solver_deposit_address": solver1_id,
             "user_deposit_hash": "hash-s1",
             "amount": s1_borrow_amount.to_string()
         }))?
@@ -175,7 +175,7 @@ async fn test_multi_solver() -> Result<(), Box<dyn std::error::Error + Send + Sy
     let _intent2 = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent-s2",
-            "_solver_deposit_address": solver2_id,
+            "solver_deposit_address": solver2_id,
             "user_deposit_hash": "hash-s2",
             "amount": s2_borrow_amount.to_string()
         }))?
@@ -207,7 +207,7 @@ async fn test_multi_solver() -> Result<(), Box<dyn std::error::Error + Send + Sy
             "receiver_id": lender1_id
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender1_id.clone(), lender1_signer.clone())
         .send_to(&network_config)
         .await?;
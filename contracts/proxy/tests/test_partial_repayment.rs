@@ -88,7 +88,7 @@ async fn test_partial_repayment_less_than_principal() -> Result<(), Box<dyn std:
     let _intent_result = builder.vault_contract()
         .call_function("new_intent", json!({
             "intent_data": "intent-partial-test",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-partial-test",
             "amount": borrow_amount.to_string()
         }))?
@@ -216,7 +216,7 @@ async fn test_repayment_exact_principal_no_yield() -> Result<(), Box<dyn std::er
     let _intent_result = builder.vault_contract()
         .call_function("new_intent", json!({
             "intent_data": "intent-exact-repay",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-exact-repay",
             "amount": borrow_amount.to_string()
         }))?
@@ -314,7 +314,7 @@ async fn test_repayment_with_yield() -> Result<(), Box<dyn std::error::Error + S
     let _intent_result = builder.vault_contract()
         .call_function("new_intent", json!({
             "intent_data": "intent-with-yield",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-with-yield",
             "amount": borrow_amount.to_string()
         }))?
@@ -446,7 +446,7 @@ async fn test_repayment_with_extra_yield() -> Result<(), Box<dyn std::error::Err
     let _intent_result = builder.vault_contract()
         .call_function("new_intent", json!({
             "intent_data": "intent-extra-yield",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-extra-yield",
             "amount": borrow_amount.to_string()
         }))?
@@ -0,0 +1,139 @@
+//! # Protocol Fees Test
+//!
+//! Tests that a protocol fee on repayment yield accrues across repayments
+//! and reaches the treasury through `claim_protocol_fees`.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_protocol_fees` | Two repayments with a 20% protocol fee, then a treasury claim | Treasury receives 20% of both yields, lenders keep the rest |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Owner sets protocol_fee_bps = 2000 and treasury_id
+//! 2. Lender deposits 100 USDC
+//! 3. Solver borrows and repays 10 USDC twice (1% yield each)
+//! 4. Treasury claims the accrued fees
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{
+    deposit_to_vault, get_balance, get_total_assets, solver_borrow, solver_repay,
+    TestScenarioBuilder,
+};
+use near_api::Data;
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Reads `get_accrued_protocol_fees` from the vault.
+async fn accrued_fees(
+    builder: &TestScenarioBuilder,
+) -> Result<u128, Box<dyn std::error::Error + Send + Sync>> {
+    let fees: Data<String> = builder
+        .vault_contract()
+        .call_function("get_accrued_protocol_fees", json!({}))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    Ok(fees.data.parse()?)
+}
+
+/// Tests fee accrual over two repayments and the treasury claim.
+///
+/// # Expected Outcome
+///
+/// - 20,000 (20% of 0.1 USDC) accrues per repayment
+/// - The treasury's token balance grows by the full 40,000
+/// - total_assets keeps the lenders' 80% of the yield
+#[tokio::test]
+async fn test_protocol_fees() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .create_account("solver")
+        .await?
+        .create_account("treasury")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 100_000_000u128; // 100 USDC
+    let borrow_amount = 10_000_000u128; // 10 USDC
+    let intent_yield = borrow_amount / 100; // 1%
+    let fee_per_repayment = intent_yield * 2_000 / 10_000;
+
+    // =========================================================================
+    // CONFIGURE PROTOCOL FEE
+    // =========================================================================
+    let (treasury_id, treasury_signer, _) = builder
+        .get_account("treasury")
+        .ok_or("Account treasury not found")?;
+    let owner_id = builder.genesis_account_id().clone();
+    let owner_signer = builder.genesis_signer().clone();
+    builder
+        .vault_contract()
+        .call_function("set_protocol_fee_bps", json!({ "protocol_fee_bps": 2000 }))?
+        .transaction()
+        .with_signer(owner_id.clone(), owner_signer.clone())
+        .send_to(builder.network_config())
+        .await?;
+    builder
+        .vault_contract()
+        .call_function("set_treasury", json!({ "treasury_id": treasury_id }))?
+        .transaction()
+        .with_signer(owner_id, owner_signer)
+        .send_to(builder.network_config())
+        .await?;
+
+    // =========================================================================
+    // TWO BORROW/REPAY ROUNDS
+    // =========================================================================
+    deposit_to_vault(&builder, "lender", deposit_amount).await?;
+
+    solver_borrow(&builder, borrow_amount, "hash-fee-1").await?;
+    solver_repay(&builder, 0, borrow_amount, intent_yield).await?;
+    assert_eq!(accrued_fees(&builder).await?, fee_per_repayment);
+
+    solver_borrow(&builder, borrow_amount, "hash-fee-2").await?;
+    solver_repay(&builder, 1, borrow_amount, intent_yield).await?;
+    assert_eq!(accrued_fees(&builder).await?, 2 * fee_per_repayment);
+
+    let lender_assets = get_total_assets(&builder).await?;
+    assert_eq!(
+        lender_assets,
+        deposit_amount + 2 * (intent_yield - fee_per_repayment)
+    );
+
+    // =========================================================================
+    // TREASURY CLAIMS
+    // =========================================================================
+    let treasury_before = get_balance(&builder, "treasury").await?;
+    let outcome = builder
+        .vault_contract()
+        .call_function("claim_protocol_fees", json!({}))?
+        .transaction()
+        .with_signer(treasury_id.clone(), treasury_signer.clone())
+        .send_to(builder.network_config())
+        .await?;
+    let logs: Vec<String> = outcome
+        .receipts_outcome
+        .iter()
+        .flat_map(|receipt| receipt.outcome.logs.clone())
+        .collect();
+    assert!(logs.iter().any(|log| log.contains("protocol_fees_claimed")));
+
+    sleep(Duration::from_millis(1200)).await;
+
+    let treasury_after = get_balance(&builder, "treasury").await?;
+    assert_eq!(treasury_after - treasury_before, 2 * fee_per_repayment);
+    assert_eq!(accrued_fees(&builder).await?, 0);
+    assert_eq!(get_total_assets(&builder).await?, lender_assets);
+
+    Ok(())
+}
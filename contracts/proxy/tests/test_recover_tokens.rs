@@ -0,0 +1,169 @@
+//! # Token Recovery Test
+//!
+//! Tests that the owner can pull a stray non-asset token out of the vault
+//! with `recover_tokens`, and can never use it on the vault's own asset.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_recover_stray_token` | DAI is sent to the vault with a plain `ft_transfer`, then recovered | Recipient receives the DAI, `tokens_recovered` event logged |
+//! | `test_recover_asset_rejected` | Owner tries to recover the vault's USDC | Transaction fails, vault assets unchanged |
+//!
+//! ## Interaction Flow
+//!
+//! ```text
+//! 1. Deploy vault (USDC asset) and a second mock token (DAI)
+//! 2. Genesis ft_transfer's 10 DAI straight to the vault
+//! 3. Owner calls recover_tokens(dai, user, 10 DAI)
+//! 4. User holds 10 DAI, vault holds none
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{deposit_to_vault, get_total_assets, TestScenarioBuilder};
+use helpers::*;
+use near_api::{Contract, Data, NearToken};
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Tests recovering a token sent to the vault outside `ft_on_transfer`.
+///
+/// # Expected Outcome
+///
+/// - The user's DAI balance equals the recovered amount
+/// - The vault's DAI balance is 0
+#[tokio::test]
+async fn test_recover_stray_token() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let sandbox = near_sandbox::Sandbox::start_sandbox().await?;
+    let network_config = create_network_config(&sandbox);
+    let (genesis_account_id, genesis_signer) = setup_genesis_account().await;
+
+    let vault_id = deploy_vault_contract(&network_config, &genesis_account_id, &genesis_signer).await?;
+    let dai_id = deploy_named_mock_ft(
+        &network_config,
+        &genesis_account_id,
+        &genesis_signer,
+        "dai",
+        "1000000000000",
+    )
+    .await?;
+    let (user_id, _) =
+        create_user_account(&network_config, &genesis_account_id, &genesis_signer, "user").await?;
+
+    let dai_contract = Contract(dai_id.clone());
+    let vault_contract = Contract(vault_id.clone());
+
+    for account_id in [&user_id, &vault_id] {
+        dai_contract
+            .call_function("storage_deposit", json!({ "account_id": account_id }))?
+            .transaction()
+            .deposit(NearToken::from_millinear(10))
+            .with_signer(genesis_account_id.clone(), genesis_signer.clone())
+            .send_to(&network_config)
+            .await?;
+    }
+
+    // =========================================================================
+    // DAI LANDS IN THE VAULT WITHOUT ft_on_transfer
+    // =========================================================================
+    let amount = 10_000_000u128; // 10 DAI
+    dai_contract
+        .call_function("ft_transfer", json!({
+            "receiver_id": vault_id,
+            "amount": amount.to_string()
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(genesis_account_id.clone(), genesis_signer.clone())
+        .send_to(&network_config)
+        .await?;
+
+    // =========================================================================
+    // OWNER RECOVERS IT
+    // =========================================================================
+    let outcome = vault_contract
+        .call_function("recover_tokens", json!({
+            "token": dai_id,
+            "recipient": user_id,
+            "amount": amount.to_string()
+        }))?
+        .transaction()
+        .with_signer(genesis_account_id.clone(), genesis_signer.clone())
+        .send_to(&network_config)
+        .await?;
+    let logs: Vec<String> = outcome
+        .receipts_outcome
+        .iter()
+        .flat_map(|receipt| receipt.outcome.logs.clone())
+        .collect();
+    assert!(logs.iter().any(|log| log.contains("tokens_recovered")));
+
+    sleep(Duration::from_millis(1200)).await;
+
+    let user_balance: Data<String> = dai_contract
+        .call_function("ft_balance_of", json!({ "account_id": user_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(user_balance.data, amount.to_string());
+
+    let vault_balance: Data<String> = dai_contract
+        .call_function("ft_balance_of", json!({ "account_id": vault_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(vault_balance.data, "0");
+
+    Ok(())
+}
+
+/// Tests that the vault's own asset cannot be recovered.
+///
+/// # Expected Outcome
+///
+/// - The `recover_tokens` transaction fails
+/// - total_assets and the vault's USDC balance are unchanged
+#[tokio::test]
+async fn test_recover_asset_rejected() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 10_000_000u128; // 10 USDC
+    deposit_to_vault(&builder, "lender", deposit_amount).await?;
+
+    let outcome = builder
+        .vault_contract()
+        .call_function("recover_tokens", json!({
+            "token": builder.ft_contract().0,
+            "recipient": builder.genesis_account_id(),
+            "amount": deposit_amount.to_string()
+        }))?
+        .transaction()
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(builder.network_config())
+        .await?;
+
+    let status_str = format!("{:?}", outcome.status);
+    assert!(
+        status_str.contains("Cannot recover the vault asset"),
+        "expected the asset recovery to fail, got {status_str}"
+    );
+    assert_eq!(get_total_assets(&builder).await?, deposit_amount);
+
+    let vault_balance: Data<String> = builder
+        .ft_contract()
+        .call_function("ft_balance_of", json!({ "account_id": builder.vault_id() }))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    assert_eq!(vault_balance.data, deposit_amount.to_string());
+
+    Ok(())
+}
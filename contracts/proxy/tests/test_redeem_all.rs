@@ -0,0 +1,100 @@
+//! # Redeem All Test
+//!
+//! Tests `redeem_all`, which redeems the caller's entire share balance in one
+//! call and falls back to the redemption queue when the vault is illiquid.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_redeem_all` | Lender calls `redeem_all` while the solver holds all liquidity | Full balance queued, shares reach zero once the queue is processed |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Lender deposits 5 USDC
+//! 2. Solver borrows 5 USDC (all liquidity)
+//! 3. Lender calls redeem_all -> full balance QUEUED
+//! 4. Solver repays 5.05 USDC (principal + 1% yield)
+//! 5. Queue is processed -> lender holds zero shares
+//! ```
+
+mod helpers;
+
+use helpers::test_builder::{
+    deposit_to_vault, get_shares, process_redemption_queue, solver_borrow, solver_repay,
+    TestScenarioBuilder,
+};
+use helpers::REDEEM_DEPOSIT;
+use near_api::Data;
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Tests that `redeem_all` leaves the caller with no shares.
+///
+/// # Expected Outcome
+///
+/// - The full share balance sits in the queue while the solver is borrowed out
+/// - The lender holds zero shares after repayment and queue processing
+#[tokio::test]
+async fn test_redeem_all() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .create_account("solver")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 5_000_000u128; // 5 USDC
+    let intent_yield = deposit_amount / 100; // 1%
+
+    // =========================================================================
+    // LENDER DEPOSITS, SOLVER BORROWS EVERYTHING
+    // =========================================================================
+    let lender_shares = deposit_to_vault(&builder, "lender", deposit_amount).await?;
+    solver_borrow(&builder, deposit_amount, "hash-redeem-all").await?;
+
+    // =========================================================================
+    // REDEEM ALL (QUEUED)
+    // =========================================================================
+    let (lender_id, lender_signer, _) = builder
+        .get_account("lender")
+        .ok_or("Account lender not found")?;
+    builder
+        .vault_contract()
+        .call_function("redeem_all", json!({
+            "receiver_id": null,
+            "memo": null,
+            "min_assets_out": null
+        }))?
+        .transaction()
+        .deposit(REDEEM_DEPOSIT)
+        .with_signer(lender_id.clone(), lender_signer.clone())
+        .send_to(builder.network_config())
+        .await?;
+
+    sleep(Duration::from_millis(1200)).await;
+
+    let queued: Data<String> = builder
+        .vault_contract()
+        .call_function("get_pending_redemptions_length", json!([]))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    assert_eq!(queued.data, "1", "redeem_all should queue when illiquid");
+    assert_eq!(get_shares(&builder, "lender").await?, lender_shares);
+
+    // =========================================================================
+    // REPAY AND PROCESS QUEUE
+    // =========================================================================
+    solver_repay(&builder, 0, deposit_amount, intent_yield).await?;
+    process_redemption_queue(&builder).await?;
+
+    assert_eq!(get_shares(&builder, "lender").await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! # Redeem To Chain Test
+//!
+//! Tests `redeem_to_chain`, which burns vault shares and routes the redeemed
+//! assets straight into the OMFT bridge instead of a NEAR account.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_redeem_to_chain` | Lender redeems half their shares to an EVM address | Shares burn, bridge transfer carries the redeemed amount and `WITHDRAW_TO` memo |
+//!
+//! ## Stubbed Bridge
+//!
+//! The mock FT stands in for the OMFT token. It accepts an `ft_transfer` to
+//! itself like any other transfer, so the "bridged" assets simply land in the
+//! token contract's own balance. That balance and the transfer's memo are
+//! what the test checks.
+//!
+//! ## Key Verification Points
+//!
+//! - The lender's shares drop by exactly the redeemed amount
+//! - The token contract's own balance grows by the redeemed assets
+//! - The `ft_transfer` log carries `WITHDRAW_TO:<evm_address>`
+//! - total_assets drops by the redeemed assets
+
+mod helpers;
+
+use helpers::test_builder::{deposit_to_vault, get_shares, get_total_assets, TestScenarioBuilder};
+use near_api::{Data, NearToken};
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Tests a bridged redemption against the stubbed OMFT bridge.
+///
+/// # Scenario
+///
+/// 1. Lender deposits 10 USDC
+/// 2. The token contract is registered on itself so it can receive the bridge transfer
+/// 3. Lender calls `redeem_to_chain` for half their shares to an EVM address
+///
+/// # Expected Outcome
+///
+/// - Lender keeps half their shares
+/// - 5 USDC reach the token contract with the bridge memo
+#[tokio::test]
+async fn test_redeem_to_chain() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = TestScenarioBuilder::new()
+        .await?
+        .deploy_vault()
+        .await?
+        .create_account("lender")
+        .await?
+        .register_accounts()
+        .await?;
+
+    let deposit_amount = 10_000_000u128; // 10 USDC
+    let evm_address = "0x1111111111111111111111111111111111111111";
+    let ft_id = builder.ft_contract().0.clone();
+
+    // =========================================================================
+    // LENDER DEPOSITS
+    // =========================================================================
+    let lender_shares = deposit_to_vault(&builder, "lender", deposit_amount).await?;
+    let redeemed_shares = lender_shares / 2;
+
+    // Register the token contract on itself (bridge transfer target)
+    builder
+        .ft_contract()
+        .call_function("storage_deposit", json!({ "account_id": ft_id }))?
+        .transaction()
+        .deposit(NearToken::from_millinear(10))
+        .with_signer(builder.genesis_account_id().clone(), builder.genesis_signer().clone())
+        .send_to(builder.network_config())
+        .await?;
+
+    let bridge_balance_before: Data<String> = builder
+        .ft_contract()
+        .call_function("ft_balance_of", json!({ "account_id": ft_id }))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    let bridge_balance_before: u128 = bridge_balance_before.data.parse()?;
+
+    // =========================================================================
+    // REDEEM TO CHAIN
+    // =========================================================================
+    let (lender_id, lender_signer, _) = builder
+        .get_account("lender")
+        .ok_or("Account lender not found")?;
+    let outcome = builder
+        .vault_contract()
+        .call_function("redeem_to_chain", json!({
+            "shares": redeemed_shares.to_string(),
+            "chain": "evm",
+            "recipient": evm_address,
+            "memo": "to mainnet"
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(lender_id.clone(), lender_signer.clone())
+        .send_to(builder.network_config())
+        .await?;
+
+    println!("redeem_to_chain outcome status: {:?}", outcome.status);
+    let logs: Vec<String> = outcome
+        .receipts_outcome
+        .iter()
+        .flat_map(|receipt| receipt.outcome.logs.clone())
+        .collect();
+    for log in &logs {
+        println!("  log: {}", log);
+    }
+    assert!(format!("{:?}", outcome.status).contains("Success"));
+
+    sleep(Duration::from_millis(1200)).await;
+
+    // =========================================================================
+    // VERIFY BURN AND BRIDGE CALL
+    // =========================================================================
+    let expected_assets = deposit_amount / 2;
+
+    let lender_shares_after = get_shares(&builder, "lender").await?;
+    assert_eq!(lender_shares_after, lender_shares - redeemed_shares);
+
+    let bridge_balance_after: Data<String> = builder
+        .ft_contract()
+        .call_function("ft_balance_of", json!({ "account_id": ft_id }))?
+        .read_only()
+        .fetch_from(builder.network_config())
+        .await?;
+    let bridge_balance_after: u128 = bridge_balance_after.data.parse()?;
+    assert_eq!(bridge_balance_after - bridge_balance_before, expected_assets);
+
+    let bridge_memo = format!("WITHDRAW_TO:{}", evm_address);
+    assert!(
+        logs.iter()
+            .any(|log| log.contains("ft_transfer") && log.contains(&bridge_memo)),
+        "bridge transfer with memo {} not found",
+        bridge_memo
+    );
+    assert!(logs.iter().any(|log| log.contains("vault_redeem_to_chain")));
+
+    let total_assets = get_total_assets(&builder).await?;
+    assert_eq!(total_assets, deposit_amount - expected_assets);
+
+    Ok(())
+}
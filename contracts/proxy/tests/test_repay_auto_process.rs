@@ -0,0 +1,326 @@
+//! # Auto-Processing Repayment Test
+//!
+//! Tests that a repayment sent with `auto_process: true` pays out queued
+//! redemptions in the same call, without a separate `process_next_redemption`.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_repay_auto_process` | Solver repays with `auto_process` while a lender is queued | Lender is paid out by the repayment itself |
+//!
+//! ## Lender/Solver Interaction Flow
+//!
+//! ```text
+//! 1. Lender deposits 5 USDC → receives vault shares
+//! 2. Solver borrows 5 USDC (all liquidity)
+//! 3. Lender tries to redeem → gets QUEUED (no liquidity)
+//! 4. Solver repays 5.05 USDC with auto_process = true (300 TGas attached)
+//! 5. Lender receives 5.05 USDC without anyone calling process_next_redemption
+//! ```
+//!
+//! ## Key Verification Points
+//!
+//! - Queue holds the lender's entry before the repayment
+//! - Queue is empty right after the repayment
+//! - Lender receives deposit + yield
+
+mod helpers;
+
+use helpers::*;
+use near_api::{Contract, Data, NearGas, NearToken};
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Tests that an auto-processing repayment drains the queue head.
+///
+/// # Scenario
+///
+/// A single lender deposits, solver borrows all, lender redeems (queued),
+/// solver repays with `auto_process: true`.
+///
+/// # Expected Outcome
+///
+/// - Queue is empty immediately after the repayment
+/// - Lender receives SOLVER_BORROW_AMOUNT + 1% yield
+/// - Vault total_assets = 0
+#[tokio::test]
+async fn test_repay_auto_process() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let sandbox = near_sandbox::Sandbox::start_sandbox().await?;
+    let network_config = create_network_config(&sandbox);
+    let (genesis_account_id, genesis_signer) = setup_genesis_account().await;
+    println!("Sandbox started, genesis account = {}", genesis_account_id);
+
+    let vault_id = deploy_vault_contract(&network_config, &genesis_account_id, &genesis_signer).await?;
+    let ft_id: near_api::AccountId = format!("usdc.{}", genesis_account_id).parse()?;
+    println!("Vault deployed at {}, FT deployed at {}", vault_id, ft_id);
+
+    let (lender_id, lender_signer) =
+        create_user_account(&network_config, &genesis_account_id, &genesis_signer, "lender").await?;
+    let (solver_id, solver_signer) =
+        create_user_account(&network_config, &genesis_account_id, &genesis_signer, "solver").await?;
+    println!("Test accounts created: lender={}, solver={}", lender_id, solver_id);
+
+    let ft_contract = Contract(ft_id.clone());
+    let vault_contract = Contract(vault_id.clone());
+
+    // Register accounts
+    for account_id in [&lender_id, &solver_id] {
+        ft_contract
+            .call_function("storage_deposit", json!({ "account_id": account_id }))?
+            .transaction()
+            .deposit(NearToken::from_millinear(10))
+            .with_signer(genesis_account_id.clone(), genesis_signer.clone())
+            .send_to(&network_config)
+            .await?;
+        println!("FT storage_deposit completed for {}", account_id);
+    }
+
+    vault_contract
+        .call_function("storage_deposit", json!({ "account_id": lender_id }))?
+        .transaction()
+        .deposit(NearToken::from_millinear(10))
+        .with_signer(lender_id.clone(), lender_signer.clone())
+        .send_to(&network_config)
+        .await?;
+    println!("Vault storage_deposit completed for {}", lender_id);
+
+    vault_contract
+        .call_function("storage_deposit", json!({ "account_id": solver_id }))?
+        .transaction()
+        .deposit(NearToken::from_millinear(10))
+        .with_signer(solver_id.clone(), solver_signer.clone())
+        .send_to(&network_config)
+        .await?;
+    println!("Vault storage_deposit completed for {}", solver_id);
+
+    // =========================================================================
+    // LENDER DEPOSITS
+    // =========================================================================
+    let deposit_amount = SOLVER_BORROW_AMOUNT;
+    ft_contract
+        .call_function("ft_transfer", json!({
+            "receiver_id": lender_id,
+            "amount": deposit_amount.to_string()
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(genesis_account_id.clone(), genesis_signer.clone())
+        .send_to(&network_config)
+        .await?;
+    println!(
+        "FT transfer genesis -> {} amount={} (fund lender)",
+        lender_id, deposit_amount
+    );
+
+    ft_contract
+        .call_function("ft_transfer_call", json!({
+            "receiver_id": vault_id,
+            "amount": deposit_amount.to_string(),
+            "msg": json!({ "receiver_id": lender_id }).to_string()
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(lender_id.clone(), lender_signer.clone())
+        .send_to(&network_config)
+        .await?;
+    println!(
+        "FT transfer_call {} -> vault amount={} (initial deposit)",
+        lender_id, deposit_amount
+    );
+
+    let lender_shares: Data<String> = vault_contract
+        .call_function("ft_balance_of", json!({ "account_id": lender_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    let lender_shares_u128 = lender_shares.data.parse::<u128>().unwrap();
+    println!(
+        "{} received vault shares amount={}",
+        lender_id, lender_shares_u128
+    );
+
+    let total_assets_before_borrow: Data<String> = vault_contract
+        .call_function("total_assets", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(total_assets_before_borrow.data, deposit_amount.to_string());
+    println!(
+        "vault total_assets before borrow = {}",
+        total_assets_before_borrow.data
+    );
+
+    // =========================================================================
+    // SOLVER BORROWS ALL LIQUIDITY
+    // =========================================================================
+    let _intent = vault_contract
+        .call_function("new_intent", json!({
+            "intent_data": "intent",
+            "solver_deposit_address": solver_id,
+            "user_deposit_hash": "hash-auto-process",
+            "amount": SOLVER_BORROW_AMOUNT.to_string()
+        }))?
+        .transaction()
+        .with_signer(solver_id.clone(), solver_signer.clone())
+        .send_to(&network_config)
+        .await?;
+    println!(
+        "{} borrowed liquidity via new_intent (intent hash 'hash-auto-process')",
+        solver_id
+    );
+
+    println!("Waiting for borrow transfer to finalize...");
+    sleep(Duration::from_millis(1200)).await;
+
+    let total_assets_after_borrow: Data<String> = vault_contract
+        .call_function("total_assets", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(total_assets_after_borrow.data, "0");
+    println!(
+        "vault total_assets immediately after borrow = {}",
+        total_assets_after_borrow.data
+    );
+
+    // =========================================================================
+    // LENDER REDEEMS (QUEUED)
+    // =========================================================================
+    let redeem_outcome = vault_contract
+        .call_function("redeem", json!({
+            "shares": lender_shares_u128.to_string(),
+            "receiver_id": lender_id,
+            "memo": null
+        }))?
+        .transaction()
+        .deposit(REDEEM_DEPOSIT)
+        .with_signer(lender_id.clone(), lender_signer.clone())
+        .send_to(&network_config)
+        .await?;
+
+    println!("queued redemption outcome: {:?}", redeem_outcome.status);
+    let status_str = format!("{:?}", redeem_outcome.status);
+    assert!(
+        status_str.contains("SuccessValue"),
+        "expected redeem outcome SuccessValue, got {status_str}"
+    );
+
+    let pending_redemptions: Data<Vec<serde_json::Value>> = vault_contract
+        .call_function("get_pending_redemptions", json!({}))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(pending_redemptions.data.len(), 1);
+    assert_eq!(
+        pending_redemptions.data[0]["shares"]
+            .as_str()
+            .expect("shares should be a string"),
+        lender_shares_u128.to_string()
+    );
+    println!(
+        "pending redemptions queued entries: {:?}",
+        pending_redemptions.data
+    );
+
+    // =========================================================================
+    // SOLVER REPAYS WITH YIELD
+    // =========================================================================
+    let intent_yield_amount = SOLVER_BORROW_AMOUNT / 100; // 1% yield
+    let total_repayment = SOLVER_BORROW_AMOUNT + intent_yield_amount;
+
+    ft_contract
+        .call_function("ft_transfer", json!({
+            "receiver_id": solver_id,
+            "amount": intent_yield_amount.to_string()
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(genesis_account_id.clone(), genesis_signer.clone())
+        .send_to(&network_config)
+        .await?;
+    println!(
+        "FT transfer genesis -> {} intent_yield amount={}",
+        solver_id, intent_yield_amount
+    );
+
+    ft_contract
+        .call_function("ft_transfer_call", json!({
+            "receiver_id": vault_id,
+            "amount": total_repayment.to_string(),
+            "msg": json!({ "repay": { "intent_index": "0", "auto_process": true } }).to_string()
+        }))?
+        .transaction()
+        .gas(NearGas::from_tgas(300))
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(solver_id.clone(), solver_signer.clone())
+        .send_to(&network_config)
+        .await?;
+    println!(
+        "FT transfer_call {} -> vault repayment amount={} (principal + intent_yield, auto_process)",
+        solver_id, total_repayment
+    );
+
+    // Wait for repayment to finalize
+    sleep(Duration::from_millis(2000)).await;
+    
+    // =========================================================================
+    // QUEUE WAS PROCESSED BY THE REPAYMENT
+    // =========================================================================
+    let queue_length: Data<String> = vault_contract
+        .call_function("get_pending_redemptions_length", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(queue_length.data, "0");
+    println!("queue length right after auto-processing repayment = {}", queue_length.data);
+
+    // =========================================================================
+    // VERIFY FINAL STATE
+    // =========================================================================
+    let lender_final_balance: Data<String> = ft_contract
+        .call_function("ft_balance_of", json!({ "account_id": lender_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(lender_final_balance.data, total_repayment.to_string());
+    println!(
+        "{} final FT balance after repayment processing = {}",
+        lender_id, lender_final_balance.data
+    );
+
+    let total_assets_final: Data<String> = vault_contract
+        .call_function("total_assets", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(total_assets_final.data, "0");
+    println!(
+        "vault total_assets after repayment processing = {}",
+        total_assets_final.data
+    );
+
+    let total_shares_final: Data<String> = vault_contract
+        .call_function("ft_total_supply", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(total_shares_final.data, "0");
+    println!(
+        "vault total shares after repayment processing = {}",
+        total_shares_final.data
+    );
+
+    let pending_redemptions_final: Data<Vec<serde_json::Value>> = vault_contract
+        .call_function("get_pending_redemptions", json!({}))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert!(pending_redemptions_final.data.is_empty());
+    println!(
+        "pending redemption queue after repayment = {:?}",
+        pending_redemptions_final.data
+    );
+
+    Ok(())
+}
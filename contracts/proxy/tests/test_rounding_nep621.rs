@@ -35,6 +35,7 @@
 mod helpers;
 
 use helpers::test_builder::*;
+use helpers::REDEEM_DEPOSIT;
 use near_api::{Data, NearToken};
 use serde_json::json;
 use tokio::time::{sleep, Duration};
@@ -184,7 +185,7 @@ async fn test_micro_transaction_attack_prevention() -> Result<(), Box<dyn std::e
                 "receiver_id": attacker_id
             }))?
             .transaction()
-            .deposit(NearToken::from_yoctonear(1))
+            .deposit(REDEEM_DEPOSIT)
             .with_signer(attacker_id.clone(), attacker_signer.clone())
             .send_to(network_config)
             .await?;
@@ -414,7 +415,7 @@ async fn test_redemption_rounds_down() -> Result<(), Box<dyn std::error::Error +
             "receiver_id": lender1_id
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender1_id.clone(), lender1_signer.clone())
         .send_to(network_config)
         .await?;
@@ -161,7 +161,7 @@ async fn test_single_lender_queue() -> Result<(), Box<dyn std::error::Error + Se
     let _intent = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "intent",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-queue",
             "amount": SOLVER_BORROW_AMOUNT.to_string()
         }))?
@@ -198,7 +198,7 @@ async fn test_single_lender_queue() -> Result<(), Box<dyn std::error::Error + Se
             "memo": null
         }))?
         .transaction()
-        .deposit(NearToken::from_yoctonear(1))
+        .deposit(REDEEM_DEPOSIT)
         .with_signer(lender_id.clone(), lender_signer.clone())
         .send_to(&network_config)
         .await?;
@@ -155,7 +155,7 @@ async fn test_solver_borrow() -> Result<(), Box<dyn std::error::Error + Send + S
     let _new_intent_result = vault_contract
         .call_function("new_intent", json!({
             "intent_data": "test-intent",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-123",
             "amount": SOLVER_BORROW_AMOUNT.to_string()
         }))?
@@ -75,7 +75,7 @@ async fn test_solver_borrow_empty_pool() -> Result<(), Box<dyn std::error::Error
     let intent_result = builder.vault_contract()
         .call_function("new_intent", json!({
             "intent_data": "intent-empty",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-empty",
             "amount": borrow_amount.to_string()
         }))?
@@ -85,7 +85,7 @@ async fn test_solver_borrow_exact_pool_size() -> Result<(), Box<dyn std::error::
     let intent_result = builder.vault_contract()
         .call_function("new_intent", json!({
             "intent_data": "intent-exact",
-            "_solver_deposit_address": solver_id,
+            "solver_deposit_address": solver_id,
             "user_deposit_hash": "hash-exact",
             "amount": exact_borrow.to_string()
         }))?
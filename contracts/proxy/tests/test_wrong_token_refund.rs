@@ -0,0 +1,149 @@
+//! # Wrong Token Refund Test
+//!
+//! Tests that `ft_transfer_call` of a token other than the vault's asset is
+//! refunded to the sender instead of failing or minting shares.
+//!
+//! ## Test Overview
+//!
+//! | Test | Description | Expected Outcome |
+//! |------|-------------|------------------|
+//! | `test_wrong_token_refund` | User sends a non-asset FT to the vault with a deposit message | Transfer succeeds, tokens bounce back, no shares minted |
+//!
+//! ## Interaction Flow
+//!
+//! ```text
+//! 1. Deploy vault (USDC asset) and a second mock token (DAI)
+//! 2. User receives 10 DAI
+//! 3. User ft_transfer_call's 10 DAI to the vault with a deposit message
+//! 4. Vault returns the full amount from ft_on_transfer
+//! 5. DAI's ft_resolve_transfer refunds the user
+//! ```
+//!
+//! ## Key Verification Points
+//!
+//! - The transaction itself succeeds
+//! - User's DAI balance is unchanged
+//! - Vault holds no DAI and minted no shares
+
+mod helpers;
+
+use helpers::*;
+use near_api::{Contract, Data, NearToken};
+use serde_json::json;
+use tokio::time::{sleep, Duration};
+
+/// Tests that non-asset tokens are refunded by `ft_on_transfer`.
+///
+/// # Scenario
+///
+/// A user transfers a second mock token into the vault with a deposit message.
+///
+/// # Expected Outcome
+///
+/// - ft_transfer_call status is SuccessValue
+/// - User's token balance is restored
+/// - Vault share supply and total_assets stay 0
+#[tokio::test]
+async fn test_wrong_token_refund() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let sandbox = near_sandbox::Sandbox::start_sandbox().await?;
+    let network_config = create_network_config(&sandbox);
+    let (genesis_account_id, genesis_signer) = setup_genesis_account().await;
+
+    let vault_id = deploy_vault_contract(&network_config, &genesis_account_id, &genesis_signer).await?;
+    let dai_id = deploy_named_mock_ft(
+        &network_config,
+        &genesis_account_id,
+        &genesis_signer,
+        "dai",
+        "1000000000000",
+    )
+    .await?;
+    println!("Vault deployed at {}, stray token deployed at {}", vault_id, dai_id);
+
+    let (user_id, user_signer) =
+        create_user_account(&network_config, &genesis_account_id, &genesis_signer, "user").await?;
+
+    let dai_contract = Contract(dai_id.clone());
+    let vault_contract = Contract(vault_id.clone());
+
+    // Register user and vault with the stray token
+    for account_id in [&user_id, &vault_id] {
+        dai_contract
+            .call_function("storage_deposit", json!({ "account_id": account_id }))?
+            .transaction()
+            .deposit(NearToken::from_millinear(10))
+            .with_signer(genesis_account_id.clone(), genesis_signer.clone())
+            .send_to(&network_config)
+            .await?;
+    }
+
+    let amount = 10_000_000u128; // 10 DAI
+    dai_contract
+        .call_function("ft_transfer", json!({
+            "receiver_id": user_id,
+            "amount": amount.to_string()
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(genesis_account_id.clone(), genesis_signer.clone())
+        .send_to(&network_config)
+        .await?;
+
+    // =========================================================================
+    // USER SENDS THE WRONG TOKEN
+    // =========================================================================
+    let outcome = dai_contract
+        .call_function("ft_transfer_call", json!({
+            "receiver_id": vault_id,
+            "amount": amount.to_string(),
+            "msg": json!({ "deposit": {} }).to_string()
+        }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(1))
+        .with_signer(user_id.clone(), user_signer.clone())
+        .send_to(&network_config)
+        .await?;
+
+    let status_str = format!("{:?}", outcome.status);
+    println!("wrong token ft_transfer_call status: {}", status_str);
+    assert!(
+        status_str.contains("SuccessValue"),
+        "expected the transfer to succeed with a refund, got {status_str}"
+    );
+
+    sleep(Duration::from_millis(1200)).await;
+
+    // =========================================================================
+    // VERIFY REFUND
+    // =========================================================================
+    let user_balance: Data<String> = dai_contract
+        .call_function("ft_balance_of", json!({ "account_id": user_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(user_balance.data, amount.to_string());
+
+    let vault_balance: Data<String> = dai_contract
+        .call_function("ft_balance_of", json!({ "account_id": vault_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(vault_balance.data, "0");
+
+    let total_shares: Data<String> = vault_contract
+        .call_function("ft_total_supply", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(total_shares.data, "0");
+
+    let total_assets: Data<String> = vault_contract
+        .call_function("total_assets", json!([]))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?;
+    assert_eq!(total_assets.data, "0");
+    println!("stray token refunded: user balance = {}", user_balance.data);
+
+    Ok(())
+}
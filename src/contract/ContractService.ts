@@ -87,7 +87,7 @@ export class ContractService {
         try {
             const args: Record<string, string> = {
                 intent_data: intentData,
-                _solver_deposit_address: solverDepositAddress,
+                solver_deposit_address: solverDepositAddress,
                 user_deposit_hash: userDepositHash,
             };
 